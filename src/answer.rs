@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use num::{BigInt, ToPrimitive};
+
+
+/*
+ * The result of a single puzzle part. Numbers are kept as i64 where they
+ * fit and fall back to BigInt otherwise, so Signed(5) and Big(5) are the
+ * same answer as far as comparisons are concerned.
+ */
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Signed(i64),
+    Big(BigInt),
+    Text(String)
+}
+
+
+impl Answer {
+    fn to_big(&self) -> Option<BigInt> {
+        match self {
+            Answer::Signed(n) => Some(BigInt::from(*n)),
+            Answer::Big(n) => Some(n.clone()),
+            Answer::Text(_) => None,
+        }
+    }
+}
+
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Answer {
+        Answer::Signed(n)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Answer {
+        Answer::Signed(n as i64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Answer {
+        match i64::try_from(n) {
+            Ok(n) => Answer::Signed(n),
+            Err(_) => Answer::Big(BigInt::from(n)),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::from(n as u64)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(n: BigInt) -> Answer {
+        match n.to_i64() {
+            Some(n) => Answer::Signed(n),
+            None => Answer::Big(n),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(s.to_string())
+    }
+}
+
+
+/* Numbers are read as numbers where possible, anything else is text */
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Answer, Self::Err> {
+        if let Ok(n) = s.parse::<i64>() {
+            return Ok(Answer::Signed(n));
+        }
+
+        if let Ok(n) = s.parse::<BigInt>() {
+            return Ok(Answer::Big(n));
+        }
+
+        Ok(Answer::Text(s.to_string()))
+    }
+}
+
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::Big(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+
+/* Numbers order numerically and text orders as text, the two never compare */
+impl PartialOrd for Answer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Answer::Signed(a), Answer::Signed(b)) => Some(a.cmp(b)),
+            (Answer::Text(a), Answer::Text(b)) => Some(a.cmp(b)),
+            (Answer::Text(_), _) | (_, Answer::Text(_)) => None,
+            _ => Some(self.to_big()?.cmp(&other.to_big()?)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_compare_across_representations() {
+        let big = Answer::Big(BigInt::from(42));
+
+        assert_eq!(Answer::from(42_u64), big);
+        assert!(Answer::from(-1_i64) < big);
+        assert!(Answer::from(u64::MAX) > Answer::from(i64::MAX));
+    }
+
+    #[test]
+    fn text_never_equals_a_number() {
+        assert_ne!(Answer::from("42"), Answer::from(42_i64));
+        assert_eq!(Answer::from("42").partial_cmp(&Answer::from(42_i64)), None);
+    }
+
+    #[test]
+    fn parses_numbers_before_text() {
+        let huge = "123456789012345678901234567890";
+
+        assert_eq!("-7".parse::<Answer>().unwrap(), Answer::Signed(-7));
+        assert!(matches!(huge.parse::<Answer>().unwrap(), Answer::Big(_)));
+        assert_eq!(huge.parse::<Answer>().unwrap().to_string(), huge);
+        assert_eq!("LRLR".parse::<Answer>().unwrap(), Answer::from("LRLR"));
+    }
+}
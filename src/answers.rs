@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::answer::Answer;
+use crate::days::Part;
+use crate::error::{Error, Result};
+
+
+/*
+ * Known correct answers, read from a small subset of TOML:
+ *
+ *     # comment
+ *     [day_1]
+ *     part1 = 54708
+ *     part2 = "text answer"
+ *
+ * Bare values are read as numbers where possible, quoted values are
+ * always text.
+ */
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(u32, Part), Answer>
+}
+
+
+impl Answers {
+    pub fn load(file_name: &str) -> Result<Answers> {
+        Answers::parse(&fs::read_to_string(file_name)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Answers> {
+        let mut answers = HashMap::new();
+        let mut day = None;
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = strip_comment(raw).trim();
+            let column = raw.len() - raw.trim_start().len() + 1;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let Some(n) = header.strip_suffix(']')
+                    .and_then(|h| h.strip_prefix("day_"))
+                    .and_then(|n| n.parse::<u32>().ok()) else {
+                    return Err(Error::parse(i + 1, column, raw, "Expected a header of the form [day_N]"));
+                };
+
+                day = Some(n);
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(Error::parse(i + 1, column, raw, "Expected part1 = <answer> or part2 = <answer>"));
+            };
+
+            let part = match key.trim() {
+                "part1" => Part::P1,
+                "part2" => Part::P2,
+                _ => return Err(Error::parse(i + 1, column, raw, "Unknown key, expected part1 or part2")),
+            };
+
+            let Some(day) = day else {
+                return Err(Error::parse(i + 1, column, raw, "Answer given before any [day_N] header"));
+            };
+
+            let value_column = raw.find('=').unwrap_or(0) + 2;
+            let Some(answer) = parse_value(value.trim()) else {
+                return Err(Error::parse(i + 1, value_column, raw, "Expected a number or a quoted string"));
+            };
+
+            answers.insert((day, part), answer);
+        }
+
+        Ok(Answers { answers })
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+}
+
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+
+    for (i, ch) in line.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => ()
+        };
+    }
+
+    line
+}
+
+
+fn parse_value(value: &str) -> Option<Answer> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        return quoted.strip_suffix('"').map(Answer::from);
+    }
+
+    if value.is_empty() || value.contains(char::is_whitespace) {
+        return None;
+    }
+
+    value.parse().ok()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_numbers_and_text() {
+        let answers = Answers::parse("\
+# Recorded answers
+[day_1]
+part1 = 54708 # first star
+part2 = -3
+
+[day_8]
+part1 = \"a # b\"
+").unwrap();
+
+        assert_eq!(answers.get(1, Part::P1), Some(&Answer::from(54708_u64)));
+        assert_eq!(answers.get(1, Part::P2), Some(&Answer::from(-3_i64)));
+        assert_eq!(answers.get(8, Part::P1), Some(&Answer::from("a # b")));
+        assert_eq!(answers.get(8, Part::P2), None);
+    }
+
+    #[test]
+    fn reports_where_the_file_is_wrong() {
+        let Err(Error::Parse { line, .. }) = Answers::parse("[day_1]\npart3 = 1") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(line, 2);
+        assert!(Answers::parse("part1 = 1").is_err());
+        assert!(Answers::parse("[day_x]").is_err());
+    }
+}
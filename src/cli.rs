@@ -0,0 +1,258 @@
+use crate::days::Part;
+use crate::log::Level;
+
+
+pub const USAGE: &str = "\
+Usage:
+    aoc run <day|all> [<path>|-] [--part <1|2>] [--input <path>]
+    aoc bench <day|all> [<path>|-] [--part <1|2>] [--runs <n>]
+    aoc verify [day|all] [--answers <path>]
+    aoc explain <day> <query> [--part <1|2>] [--input <path>]
+    aoc help
+
+Every command also accepts --log <off|info|debug|trace> to print the
+solutions' diagnostics to stderr (default: off).
+
+Options:
+    -p, --part <1|2>       Only run the given part (default: both)
+    -i, --input <path>     Read the puzzle input from <path>, or from
+                           stdin when <path> is - (default:
+                           inputs/day_<day>.txt)
+    -n, --runs <n>         Number of times bench repeats each day
+                           (default: 10)
+    -a, --answers <path>   Check results against the answers in <path>
+                           (default: answers.toml)
+
+explain describes how a day treats one item, such as a day 7 hand
+(aoc explain 7 KTJJT), against the rest of its input. Day 8 draws its
+network from a node, or all of it, as Graphviz DOT with the loop each
+walker falls into highlighted (aoc explain 8 AAA -p 2 > network.dot).";
+
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    Run {
+        days: DaySelection,
+        part: Option<Part>,
+        input: Option<String>
+    },
+    Bench {
+        days: DaySelection,
+        part: Option<Part>,
+        input: Option<String>,
+        runs: usize
+    },
+    Verify {
+        days: DaySelection,
+        answers: Option<String>
+    },
+    Explain {
+        day: u32,
+        query: String,
+        part: Option<Part>,
+        input: Option<String>
+    },
+    Help
+}
+
+pub const DEFAULT_RUNS: usize = 10;
+
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub log_level: Level
+}
+
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum DaySelection {
+    All,
+    Day(u32)
+}
+
+
+/* --log may appear anywhere, so it is pulled out before the command is parsed */
+pub fn parse_args<I>(args: I) -> Result<Args, String>
+where I: IntoIterator<Item = String> {
+    let mut args = args.into_iter();
+    let mut log_level = Level::Off;
+    let mut rest = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg != "--log" {
+            rest.push(arg);
+            continue;
+        }
+
+        let Some(value) = args.next() else {
+            return Err(format!("Missing value for '{arg}'"));
+        };
+
+        log_level = value.parse()?;
+    }
+
+    let command = parse_command(rest.into_iter())?;
+
+    Ok(Args { command, log_level })
+}
+
+
+fn parse_command<I>(mut args: I) -> Result<Command, String>
+where I: Iterator<Item = String> {
+    let Some(command) = args.next() else {
+        return Err("No command given".to_string());
+    };
+
+    match command.as_str() {
+        "run" => parse_run(args, false),
+        "bench" => parse_run(args, true),
+        "verify" => parse_verify(args),
+        "explain" => parse_explain(args),
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        _ => Err(format!("Unknown command '{command}'")),
+    }
+}
+
+
+/* Shared by run and bench, only bench accepts --runs */
+fn parse_run<I>(mut args: I, bench: bool) -> Result<Command, String>
+where I: Iterator<Item = String> {
+    let mut days = None;
+    let mut part = None;
+    let mut input = None;
+    let mut runs = DEFAULT_RUNS;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => {
+                let Some(value) = args.next() else {
+                    return Err(format!("Missing value for '{arg}'"));
+                };
+
+                part = Some(parse_part(&value)?);
+            },
+            "-i" | "--input" => {
+                let Some(value) = args.next() else {
+                    return Err(format!("Missing value for '{arg}'"));
+                };
+
+                input = Some(value);
+            },
+            "-n" | "--runs" if bench => {
+                let Some(value) = args.next() else {
+                    return Err(format!("Missing value for '{arg}'"));
+                };
+
+                runs = match value.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid run count '{value}'")),
+                };
+            },
+            _ if days.is_none() => days = Some(parse_days(&arg)?),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(format!("Unexpected argument '{arg}'")),
+        };
+    }
+
+    let Some(days) = days else {
+        return Err("No day given to run".to_string());
+    };
+
+    if days == DaySelection::All && input.is_some() {
+        return Err("'--input' cannot be used when running all days".to_string());
+    }
+
+    if bench {
+        Ok(Command::Bench { days, part, input, runs })
+    } else {
+        Ok(Command::Run { days, part, input })
+    }
+}
+
+
+fn parse_verify<I>(mut args: I) -> Result<Command, String>
+where I: Iterator<Item = String> {
+    let mut days = None;
+    let mut answers = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" | "--answers" => {
+                let Some(value) = args.next() else {
+                    return Err(format!("Missing value for '{arg}'"));
+                };
+
+                answers = Some(value);
+            },
+            _ if days.is_none() => days = Some(parse_days(&arg)?),
+            _ => return Err(format!("Unexpected argument '{arg}'")),
+        };
+    }
+
+    Ok(Command::Verify {
+        days: days.unwrap_or(DaySelection::All),
+        answers
+    })
+}
+
+
+fn parse_explain<I>(mut args: I) -> Result<Command, String>
+where I: Iterator<Item = String> {
+    let mut day = None;
+    let mut query = None;
+    let mut part = None;
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => {
+                let Some(value) = args.next() else {
+                    return Err(format!("Missing value for '{arg}'"));
+                };
+
+                part = Some(parse_part(&value)?);
+            },
+            "-i" | "--input" => {
+                let Some(value) = args.next() else {
+                    return Err(format!("Missing value for '{arg}'"));
+                };
+
+                input = Some(value);
+            },
+            _ if day.is_none() => day = match parse_days(&arg)? {
+                DaySelection::Day(day) => Some(day),
+                DaySelection::All => return Err("explain needs a single day".to_string()),
+            },
+            _ if query.is_none() => query = Some(arg),
+            _ => return Err(format!("Unexpected argument '{arg}'")),
+        };
+    }
+
+    let (Some(day), Some(query)) = (day, query) else {
+        return Err("explain needs a day and something to explain".to_string());
+    };
+
+    Ok(Command::Explain { day, query, part, input })
+}
+
+
+fn parse_days(arg: &str) -> Result<DaySelection, String> {
+    if arg == "all" {
+        return Ok(DaySelection::All);
+    }
+
+    match arg.parse::<u32>() {
+        Ok(day @ 1..=25) => Ok(DaySelection::Day(day)),
+        _ => Err(format!("Invalid day '{arg}', expected 1-25 or 'all'")),
+    }
+}
+
+
+fn parse_part(arg: &str) -> Result<Part, String> {
+    match arg {
+        "1" => Ok(Part::P1),
+        "2" => Ok(Part::P2),
+        _ => Err(format!("Invalid part '{arg}', expected 1 or 2")),
+    }
+}
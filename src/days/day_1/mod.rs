@@ -1,43 +1,55 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use crate::days::collect_lines;
+use crate::error::Result;
+use crate::answer::Answer;
+use crate::solution::Solution;
 
-use crate::days::Part;
+use std::io::BufRead;
 
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    let mut product = 0;
-    let use_words = match part {
-        Part::P1 => false,
-        Part::P2 => true
-    };
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<String>;
+
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        collect_lines(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(calibration_sum(input, false).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(calibration_sum(input, true).into())
+    }
+}
 
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to read lines");
-    };
 
-    
+fn calibration_sum(lines: &[String], use_words: bool) -> u64 {
+    let mut product = 0;
+
     for line in lines {
-        if let Ok(line) = line {
-            let v = parse_line(&line, use_words);
-            
-            let n = if v.len() == 0 {
-                0
-            } else {
-                v[0] * 10 + v[v.len() - 1]
-            };
+        let v = parse_line(line, use_words);
+        
+        let n = if v.is_empty() {
+            0
+        } else {
+            v[0] * 10 + v[v.len() - 1]
+        };
 
-            
-            product += n;
-        }
+        
+        product += n;
     }
 
-    Ok(product)
+    product
 }
 
 fn parse_line(line: &str, use_words: bool) -> Vec<u64> {
     let mut digits = Vec::new();
-    let mut prev_digit_index = 0 as usize;
+    let mut prev_digit_index = 0_usize;
 
     for (i, c) in line.chars().enumerate() {
         let maybe_digit = parse_digit(c);
@@ -79,22 +91,49 @@ fn parse_line(line: &str, use_words: bool) -> Vec<u64> {
         }
     }
 
-    return digits;
+    digits
 }
 
 fn parse_digit(c: char) -> Option<u64> {
     let d = c as i32 - '0' as i32;
 
-    if !(d >= 0 && d <= 9)  {
+    if !(0..=9).contains(&d)  {
         return None;
     }
 
     Some(d as u64)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::Day1;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE_1: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+    const EXAMPLE_2: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day1, EXAMPLE_1, P1), 142.into());
+    }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day1, EXAMPLE_2, P2), 281.into());
+    }
+}
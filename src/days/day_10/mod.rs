@@ -1,16 +1,14 @@
-use crate::days::Part;
-use crate::days::{read_lines};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::grid::Grid;
+use crate::geometry::{self, Direction, Point, Vector};
+use crate::log::{self, debug, trace, Level};
 
+use std::io::BufRead;
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
-enum Direction {
-    North,
-    South,
-    East,
-    West
-}
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 enum Pipe {
     Start,
     Ground,
@@ -22,130 +20,153 @@ enum Pipe {
     SouthAndEast,
 }
 
-#[derive(Debug)]
-struct Graph {
-    nodes: Vec<Vec<Pipe>>,
-    dists: Vec<Vec<Option<u64>>>,
-    inside: Vec<Vec<Option<bool>>>, 
-    width: usize,
-    height: usize,
+type Position = ((usize, usize), Direction);
+
+#[derive(Debug, Clone)]
+pub struct Graph {
+    nodes: Grid<Pipe>,
     start_pos: (usize, usize)
 }
 
-
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
-    }
+/* The loop through the start, with S replaced by the pipe it stands for */
+#[derive(Debug)]
+struct Loop {
+    graph: Graph,
+    /* Every tile in the order it is walked, starting at S */
+    tiles: Vec<(usize, usize)>
 }
 
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let mut graph = parse_graph(file_name)?;
-    let Some((mut p1,mut p2)) = starting_poses(&mut graph) else {
-        return Err("Invalid start position");
-    };
-    let mut distnace = 1;
+pub struct Day10;
 
-    println!("{:?}, \nStartin Directions: {:?}, {:?}", graph, p1, p2);
+impl Solution for Day10 {
+    type Input = Graph;
 
-    loop {
-        println!("P1: {:?}, P2: {:?}", p1, p2);
+    const DAY: u32 = 10;
+    const TITLE: &'static str = "Pipe Maze";
 
-        graph.dists[p1.0.1][p1.0.0] = Some(distnace);
-        graph.dists[p2.0.1][p2.0.0] = Some(distnace);
-        distnace += 1;
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_graph(input)
+    }
 
-        // print_graph(&graph);
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
 
-        if p1.0 == p2.0 {
-            break;
-        }
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
+}
 
-        let Some(p1_next) = graph.connecting_point(p1.0.0, p1.0.1, p1.1) else {
-            return Err("failed to advance p1");
-        };
 
-        let Some(p2_next) = graph.connecting_point(p2.0.0, p2.0.1, p2.1) else {
-            return Err("failed to advance p2");
-        };
+/* The farthest point is halfway round the loop */
+fn part1(graph: &Graph) -> Result<u64> {
+    let pipe_loop = find_loop(graph)?;
+
+    Ok(pipe_loop.tiles.len() as u64 / 2)
+}
 
-        p1 = p1_next;
-        p2 = p2_next;
+
+fn part2(graph: &Graph) -> Result<u64> {
+    let pipe_loop = find_loop(graph)?;
+    let enclosed = geometry::interior_points(&pipe_loop.vertices());
+
+    if log::enabled(Level::Debug) {
+        print_loop(&pipe_loop, &scanline_fill(&pipe_loop));
     }
 
-    Ok(distnace - 1)
+    Ok(enclosed as u64)
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let mut graph = parse_graph(file_name)?;
-    let Some((mut p1,mut p2)) = starting_poses(&mut graph) else {
-        return Err("Invalid start position");
+fn find_loop(graph: &Graph) -> Result<Loop> {
+    let mut graph = graph.clone();
+    let Some((mut position, _)) = starting_poses(&mut graph) else {
+        return Err(Error::invalid_state("Start does not connect to exactly two pipes"));
     };
-    let mut distnace = 1;
 
-    graph.dists[graph.start_pos.1][graph.start_pos.0] = Some(0);
+    debug!("{:?}, \nStarting Direction: {:?}", graph, position);
 
-    loop {
-        graph.dists[p1.0.1][p1.0.0] = Some(distnace);
-        graph.dists[p2.0.1][p2.0.0] = Some(distnace);
-        distnace += 1;
+    let mut tiles = vec![graph.start_pos];
 
-        if p1.0 == p2.0 {
-            break;
-        }
+    while position.0 != graph.start_pos {
+        trace!("{:?}", position);
+        tiles.push(position.0);
 
-        let Some(p1_next) = graph.connecting_point(p1.0.0, p1.0.1, p1.1) else {
-            return Err("failed to advance p1");
+        let Some(next) = graph.connecting_point(position.0.0, position.0.1, position.1) else {
+            return Err(Error::invalid_state(format!("Loop broken after {:?}", position.0)));
         };
 
-        let Some(p2_next) = graph.connecting_point(p2.0.0, p2.0.1, p2.1) else {
-            return Err("failed to advance p2");
-        };
+        position = next;
+    }
+
+    Ok(Loop { graph, tiles })
+}
 
-        p1 = p1_next;
-        p2 = p2_next;
+
+impl Loop {
+    /* The corners of the loop, in walking order */
+    fn vertices(&self) -> Vec<Point> {
+        self.tiles.iter()
+            .filter(|&&tile| self.graph.nodes[tile].is_corner())
+            .map(|&tile| Point::from(tile))
+            .collect()
     }
 
-    // check_inside(&mut graph, 0, 1)?;
+    fn on_loop(&self) -> Grid<bool> {
+        let mut on_loop = Grid::filled(self.graph.nodes.width(), self.graph.nodes.height(), false);
 
-    for y in 0..graph.height {
-        for x in 0..graph.width {
-            if graph.inside[y][x] == None {
-                check_inside(&mut graph, x, y)?;
-            }
+        for &tile in &self.tiles {
+            on_loop[tile] = true;
         }
+
+        on_loop
     }
+}
 
-    let mut inside_count = 0;
 
-    for y in 0..graph.height {
-        for x in 0..graph.width {
-            if graph.inside[y][x] == Some(true) {
-                inside_count += 1;
+/*
+ * Marks every tile enclosed by the loop, scanning each row from the left
+ * and flipping between outside and inside on each crossing. Only loop
+ * tiles with a pipe leading north count, so running along a pipe flips
+ * once when it leaves on the other side and twice when it turns back.
+ */
+fn scanline_fill(pipe_loop: &Loop) -> Grid<bool> {
+    let nodes = &pipe_loop.graph.nodes;
+    let on_loop = pipe_loop.on_loop();
+    let mut inside = Grid::filled(nodes.width(), nodes.height(), false);
+
+    for y in 0..nodes.height() {
+        let mut crossings = 0;
+
+        for x in 0..nodes.width() {
+            if !on_loop[(x, y)] {
+                inside[(x, y)] = crossings % 2 == 1;
+            } else if nodes[(x, y)].accepts_heading(Direction::South).is_some() {
+                crossings += 1;
             }
         }
     }
 
-    _print_graph(&graph);
-    
-
-    Ok(inside_count)
+    inside
 }
 
 
-fn starting_poses(graph: &mut Graph) -> Option<(((usize, usize), Direction), ((usize, usize), Direction))> {
+fn starting_poses(graph: &mut Graph) -> Option<(Position, Position)> {
     let mut p1 = None;
     let mut p2 = None;
 
-    for heading in [Direction::North, Direction::South, Direction::East, Direction::West] {
+    for heading in Direction::CARDINAL {
         let Some(p) = graph.connecting_point(graph.start_pos.0, graph.start_pos.1, heading) else {
             continue;
         };
 
-        if p1 == None {
+        /* Only count neighbours whose pipe actually leads back into the start */
+        if graph.nodes[p.0].accepts_heading(heading).is_none() {
+            continue;
+        }
+
+        if p1.is_none() {
             p1 = Some(p);
         } else {
             p2 = Some(p);
@@ -153,235 +174,197 @@ fn starting_poses(graph: &mut Graph) -> Option<(((usize, usize), Direction), ((u
         }
     }
 
-    if p1 == None || p2 == None {
-        None 
-    } else {
-        let pipe = match (p1.unwrap().1, p2.unwrap().1) {
-            (Direction::North, Direction::South) => Pipe::NorthAndSouth,
-            (Direction::North, Direction::West) => Pipe::NorthAndWest,
-            (Direction::North, Direction::East) => Pipe::NorthAndEast,
-            (Direction::South, Direction::West) => Pipe::SouthAndWest,
-            (Direction::South, Direction::East) => Pipe::SouthAndEast,
-            (Direction::East, Direction::West) => Pipe::EastAndWest,
-            _ => panic!(),
-        };
-
-        graph.nodes[graph.start_pos.1][graph.start_pos.0] = pipe;
-
-        Some((p1.unwrap(), p2.unwrap()))
-    }
-}
-
-
-fn parse_graph(file_name: &str) -> Result<Graph, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to parse file");
+    let (Some(p1), Some(p2)) = (p1, p2) else {
+        return None;
     };
 
-    let mut elements = Vec::new();
-    let mut start_pos = (0, 0);
-    let mut dists = Vec::new();
-    let mut inside = Vec::new();
-
-    for l in lines {
-        let Ok(line) = l else {
-            return Err("Error in reading line");
-        };
-
-        let mut row = Vec::new();
-        let mut row_dists = Vec::new();
-        let mut row_inside = Vec::new();
-
-        for ch in line.chars() {
-            let el = match ch {
-                '|' => Pipe::NorthAndSouth,
-                '-' => Pipe::EastAndWest,
-                'L' => Pipe::NorthAndEast,
-                'J' => Pipe::NorthAndWest,
-                '7' => Pipe::SouthAndWest,
-                'F' => Pipe::SouthAndEast,
-                '.' => Pipe::Ground,
-                'S' => Pipe::Start,
-                _ => return Err("Invalid char found when parsging")
-            };
-
-            if el == Pipe::Start {
-                start_pos = (row.len(), elements.len());
-            }
-
-            row.push(el);
-            row_dists.push(None);
-            row_inside.push(None);
-        }
+    let pipe = match (p1.1, p2.1) {
+        (Direction::North, Direction::South) => Pipe::NorthAndSouth,
+        (Direction::North, Direction::West) => Pipe::NorthAndWest,
+        (Direction::North, Direction::East) => Pipe::NorthAndEast,
+        (Direction::South, Direction::West) => Pipe::SouthAndWest,
+        (Direction::East, Direction::South) => Pipe::SouthAndEast,
+        (Direction::East, Direction::West) => Pipe::EastAndWest,
+        _ => return None,
+    };
 
-        elements.push(row);
-        dists.push(row_dists);
-        inside.push(row_inside);
-    }
+    graph.nodes[graph.start_pos] = pipe;
 
-    let width = elements[0].len();
-    let height = elements.len();
-
-    Ok(Graph {
-        nodes: elements,
-        dists,
-        inside,
-        width,
-        height,
-        start_pos
-    })
+    Some((p1, p2))
 }
 
 
-fn check_inside(graph: &mut Graph, start_x: usize, start_y: usize) -> Result<(), &'static str> {
-    let mut crossed_count = 0;
-    let mut prev_x = start_x;
-
-    if graph.on_line(start_x, start_y) {
-        return Ok(());
-    }
-
-    loop {
-        let Some((new_x, _)) = graph.is_valid(prev_x, start_y, 1, 0, true) else {
-            break;
-        };
-
-        println!(" - {} {} {} {:?}", new_x, start_y, crossed_count, graph.nodes[start_y][new_x]);
-
-        if !graph.on_line(new_x, start_y) {
-            prev_x = new_x;
-            continue;
-        }
-
-        if graph.nodes[start_y][new_x] == Pipe::NorthAndSouth {
-            crossed_count += 1;
-            prev_x = new_x;
-            continue;
-        }
-
-        /* In line with pipe need to follow to see if we loop it */
-        let (heading, incoming) = match graph.nodes[start_y][new_x] {
-            Pipe::NorthAndEast => (Direction::East, Direction::South),
-            Pipe::SouthAndEast => (Direction::East, Direction::North),
-            _ => panic!(),
-        };
-
-        prev_x = new_x + 1;
-
-        loop {
-            println!("   - {} {} {} {:?}", prev_x, start_y, crossed_count, graph.nodes[start_y][prev_x]);
-
-            let Some((point, p_heading)) = graph.connecting_point(
-                prev_x, start_y, heading
-            ) else {
-                return Err("Error in following line");
-            };
-
-            println!("       - {:?} {:?}", point, p_heading);
-
-            if p_heading == heading {
-                prev_x = point.0;
-            } else if p_heading == incoming {
-                // prev_x += 1;
-                crossed_count += 1;
-                break;
-            } else {
-                // prev_x += 1;
-                break;
-            }
-        }
-    }
+fn parse_graph<B: BufRead>(input: B) -> Result<Graph> {
+    let nodes = Grid::parse(input, |ch| match ch {
+        '|' => Some(Pipe::NorthAndSouth),
+        '-' => Some(Pipe::EastAndWest),
+        'L' => Some(Pipe::NorthAndEast),
+        'J' => Some(Pipe::NorthAndWest),
+        '7' => Some(Pipe::SouthAndWest),
+        'F' => Some(Pipe::SouthAndEast),
+        '.' => Some(Pipe::Ground),
+        'S' => Some(Pipe::Start),
+        _ => None
+    })?;
+
+    let Some((start_pos, _)) = nodes.iter().find(|(_, pipe)| **pipe == Pipe::Start) else {
+        return Err(Error::invalid_state("No start position S in the maze"));
+    };
 
-    graph.inside[start_y][start_x] = Some(crossed_count % 2 != 0);
-    Ok(())
+    Ok(Graph { nodes, start_pos })
 }
 
 
 impl Graph {
     fn connecting_point(&self, x: usize, y: usize, heading: Direction) -> Option<((usize, usize), Direction)> {
-        match &self.nodes[y][x] {
+        match &self.nodes[(x, y)] {
             Pipe::Ground => None,
-            p @ _ => p.accepts_heading(heading).map(
-                |((dx, dy), outgoing)| self.is_valid(x, y, dx, dy, false).map(
+            p => p.accepts_heading(heading).and_then(|outgoing| self.is_valid(x, y, outgoing.offset()).map(
                     |point| (point, outgoing)
-                )
-            ).flatten()
+                ))
         }
     }
 
 
-    fn is_valid(&self, x: usize, y:usize, dx: isize, dy: isize, ignore_ground: bool) -> Option<(usize, usize)> {
-        let x = x.checked_add_signed(dx)?;
-        let y = y.checked_add_signed(dy)?;
+    fn is_valid(&self, x: usize, y:usize, v: Vector) -> Option<(usize, usize)> {
+        let (x, y) = self.nodes.offset(x, y, v)?;
 
-        if  x >= self.width || y >= self.height || 
-            (self.nodes[y][x] == Pipe::Ground && !ignore_ground) {
+        if self.nodes[(x, y)] == Pipe::Ground {
             None 
         } else {
             Some((x, y))
         }
     }
-
-    fn on_line(&self, x: usize, y:usize) -> bool {
-        self.dists[y][x] != None
-    }
 }
 
 
 impl Pipe {
-    fn accepts_heading(&self, heading: Direction) -> Option<((isize, isize), Direction)> {
+    /* The heading out of this pipe when entering it with heading */
+    fn accepts_heading(&self, heading: Direction) -> Option<Direction> {
         match (self, heading) {
-            (Pipe::Start, Direction::North)         => Some(((0, -1), Direction::North)),
-            (Pipe::Start, Direction::South)         => Some(((0, 1), Direction::South)),
-            (Pipe::Start, Direction::East)          => Some(((1, 0), Direction::East)),
-            (Pipe::Start, Direction::West)          => Some(((-1, 0), Direction::West)),
-            (Pipe::NorthAndSouth, Direction::South) => Some(((0, 1), Direction::South)),
-            (Pipe::NorthAndSouth, Direction::North) => Some(((0, -1), Direction::North)),
-            (Pipe::EastAndWest, Direction::West)    => Some(((-1, 0), Direction::West)),
-            (Pipe::EastAndWest, Direction::East)    => Some(((1, 0), Direction::East)),
-            (Pipe::NorthAndEast, Direction::West)   => Some(((0, -1), Direction::North)),
-            (Pipe::NorthAndEast, Direction::South)  => Some(((1, 0), Direction::East)),
-            (Pipe::NorthAndWest, Direction::East)   => Some(((0, -1), Direction::North)),
-            (Pipe::NorthAndWest, Direction::South)  => Some(((-1, 0), Direction::West)),
-            (Pipe::SouthAndWest, Direction::North)  => Some(((-1, 0), Direction::West)),
-            (Pipe::SouthAndWest, Direction::East)   => Some(((0, 1), Direction::South)),
-            (Pipe::SouthAndEast, Direction::North)  => Some(((1, 0), Direction::East)),
-            (Pipe::SouthAndEast, Direction::West)   => Some(((0, 1), Direction::South)),
+            (Pipe::Start, Direction::North)         => Some(Direction::North),
+            (Pipe::Start, Direction::South)         => Some(Direction::South),
+            (Pipe::Start, Direction::East)          => Some(Direction::East),
+            (Pipe::Start, Direction::West)          => Some(Direction::West),
+            (Pipe::NorthAndSouth, Direction::South) => Some(Direction::South),
+            (Pipe::NorthAndSouth, Direction::North) => Some(Direction::North),
+            (Pipe::EastAndWest, Direction::West)    => Some(Direction::West),
+            (Pipe::EastAndWest, Direction::East)    => Some(Direction::East),
+            (Pipe::NorthAndEast, Direction::West)   => Some(Direction::North),
+            (Pipe::NorthAndEast, Direction::South)  => Some(Direction::East),
+            (Pipe::NorthAndWest, Direction::East)   => Some(Direction::North),
+            (Pipe::NorthAndWest, Direction::South)  => Some(Direction::West),
+            (Pipe::SouthAndWest, Direction::North)  => Some(Direction::West),
+            (Pipe::SouthAndWest, Direction::East)   => Some(Direction::South),
+            (Pipe::SouthAndEast, Direction::North)  => Some(Direction::East),
+            (Pipe::SouthAndEast, Direction::West)   => Some(Direction::South),
             _ => None
         }
     }
+
+    fn is_corner(&self) -> bool {
+        matches!(self, Pipe::NorthAndEast | Pipe::NorthAndWest | Pipe::SouthAndWest | Pipe::SouthAndEast)
+    }
 }
 
 
-fn _print_graph(graph: &Graph) {
-    for y in 0..graph.height {
-        for x in 0..graph.width {
-            if graph.dists[y][x] != None {
-                let dist = graph.dists[y][x].unwrap();
+/* The loop as #, with enclosed tiles as I and everything else as O */
+fn print_loop(pipe_loop: &Loop, inside: &Grid<bool>) {
+    let on_loop = pipe_loop.on_loop();
 
-                print!("{}", dist);
+    for y in 0..on_loop.height() {
+        let line: String = (0..on_loop.width())
+            .map(|x| match (on_loop[(x, y)], inside[(x, y)]) {
+                (true, _) => '#',
+                (false, true) => 'I',
+                (false, false) => 'O',
+            })
+            .collect();
 
-                if dist < 10 {
-                    print!(" ");
-                }
+        debug!("{}", line);
+    }
+}
 
-                continue;
-            }
+#[cfg(test)]
+mod tests {
+    use super::{Day10, find_loop, parse_graph, scanline_fill};
+    use crate::geometry;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE_1: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...
+";
+
+    const EXAMPLE_2: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+";
+
+    const EXAMPLE_3: &str = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...
+";
+
+    const EXAMPLE_4: &str = "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day10, EXAMPLE_1, P1), 8.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day10, EXAMPLE_2, P2), 4.into());
+    }
 
-            if graph.inside[y][x] != None {
-                let inside = graph.inside[y][x].unwrap();
+    #[test]
+    fn part2_larger_example() {
+        assert_eq!(solve_example(&Day10, EXAMPLE_3, P2), 8.into());
+    }
 
-                if inside {
-                    print!("I ");
-                } else {
-                    print!("O ");
-                }
+    #[test]
+    fn part2_junk_pipes_example() {
+        assert_eq!(solve_example(&Day10, EXAMPLE_4, P2), 10.into());
+    }
 
-                continue;
-            }
+    #[test]
+    fn scanline_fill_agrees_with_picks_theorem() {
+        for example in [EXAMPLE_1, EXAMPLE_2, EXAMPLE_3, EXAMPLE_4] {
+            let pipe_loop = find_loop(&parse_graph(example.as_bytes()).unwrap()).unwrap();
+            let filled = scanline_fill(&pipe_loop).iter().filter(|(_, inside)| **inside).count();
 
-            print!(". ");
+            assert_eq!(filled, geometry::interior_points(&pipe_loop.vertices()));
         }
-        println!("");
     }
-}
\ No newline at end of file
+}
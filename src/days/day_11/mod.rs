@@ -1,291 +1,215 @@
-use crate::days::Part;
-use crate::days::{read_lines};
+use crate::error::Result;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::grid::Grid;
+use crate::geometry::Point;
+use crate::log::{info, debug, trace};
+
+use std::io::BufRead;
 
 use self::Element::*;
 
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
-enum Element {
+pub enum Element {
     Empty,
     Galxiy
 }
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Grid<Element>;
+
+    const DAY: u32 = 11;
+    const TITLE: &'static str = "Cosmic Expansion";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_map(input)
     }
-}
 
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
 
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
+}
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let mut map = parse_map(file_name)?;
 
-    _print_galixy(&map);
 
-    expand_galixy(&mut map);
+fn part1(map: &Grid<Element>) -> Result<u64> {
+    _print_galixy(map);
 
-    println!();
+    let map = expand_galixy(map);
+
+    debug!("");
     _print_galixy(&map);
 
     let points = get_galixies(&map);
     let mut distances = 0;
 
-    println!("{:?}", points);
+    debug!("{:?}", points);
 
     let mut count = 0;
 
     for i in 0..points.len() {
         for j in (i + 1)..points.len() {
-            let (x1, y1) = &points[i];
-            let (x2, y2) = &points[j];
-
-            let distance = 
-                (*x2 as i32 - *x1 as i32).abs() + (*y2 as i32 - *y1 as i32).abs()
-            ;
+            let distance = points[i].manhattan(points[j]);
 
             distances += distance as u64;
             count += 1;
-            println!("{} {} {}", distance, i , j);
+            trace!("{} {} {}", distance, i , j);
         }
     }
 
-    println!("{}", count);
-    println!("{}", distances);
+    info!("{}", count);
+    info!("{}", distances);
 
-    Ok(0)
+    Ok(distances)
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let map = parse_map(file_name)?;
-
-    _print_galixy(&map);
+fn part2(map: &Grid<Element>) -> Result<u64> {
+    _print_galixy(map);
 
-    let (empty_rows, empty_cols) = get_empty(&map);
+    let (empty_rows, empty_cols) = get_empty(map);
 
-    let points = get_galixies(&map);
+    let points = get_galixies(map);
 
     let mut distances = 0;
     let mut count = 0;
 
     for i in 0..points.len() {
         for j in (i + 1)..points.len() {
-            let (x1, y1) = &points[i];
-            let (x2, y2) = &points[j];
+            let Point { x: x1, y: y1 } = points[i];
+            let Point { x: x2, y: y2 } = points[j];
             let emptyy_incrase = 1000000;
 
-            let mut distance = 
-                (*x2 as i32 - *x1 as i32).abs() + (*y2 as i32 - *y1 as i32).abs()
-            ;
+            let mut distance = points[i].manhattan(points[j]);
 
-            for empty_row in &empty_rows {
-                if (*y1 < *y2 && *y1 < *empty_row && *empty_row < *y2) || 
-                    (*y2 < *y1 && *y2 < *empty_row && *empty_row < *y1) {
+            for &empty_row in &empty_rows {
+                if (y1 < y2 && y1 < empty_row && empty_row < y2) || 
+                    (y2 < y1 && y2 < empty_row && empty_row < y1) {
                     distance += emptyy_incrase - 1;
                 }
             }
 
 
-            for empty_col in &empty_cols {
-                if (*x1 < *x2 && *x1 < *empty_col && *empty_col < *x2) || 
-                    (*x2 < *x1 && *x2 < *empty_col && *empty_col < *x1) {
+            for &empty_col in &empty_cols {
+                if (x1 < x2 && x1 < empty_col && empty_col < x2) || 
+                    (x2 < x1 && x2 < empty_col && empty_col < x1) {
                     distance += emptyy_incrase - 1;
                 }
             }
 
             distances += distance as u64;
             count += 1;
-            println!("{} {} {}", distance, i , j);
+            trace!("{} {} {}", distance, i , j);
         }
     }
 
-    println!("{}", count);
+    info!("{}", count);
 
     Ok(distances)
 }
 
 
-fn get_galixies(map: &Vec<Vec<Element>>) -> Vec<(u64, u64)> {
-    let mut output = Vec::new();
-
-    for y in 0..map.len() {
-        for x in 0..map[0].len() {
-            if map[y][x] == Empty {
-                continue;
-            }
-
-            output.push(
-                (x as u64, y as u64)
-            );
-        }
-    }
-
-    return output;
+fn get_galixies(map: &Grid<Element>) -> Vec<Point> {
+    map.iter()
+        .filter(|(_, el)| **el == Galxiy)
+        .map(|(pos, _)| Point::from(pos))
+        .collect()
 }
 
 
-fn expand_galixy(galixy: &mut Vec<Vec<Element>>) {
-    let mut width = galixy[0].len();
-    let mut height = galixy.len();
-    let mut row = 0;
-    let mut column = 0;
+/* Doubles every empty row and column */
+fn expand_galixy(galixy: &Grid<Element>) -> Grid<Element> {
+    let (empty_rows, empty_cols) = get_empty(galixy);
+    let mut cells = Vec::new();
+    let mut height = 0;
 
-    /* Add new Rows */
-    loop {
-        if row >= height {
-            break;
-        }
+    for (y, row) in galixy.rows().enumerate() {
+        let copies = if empty_rows.contains(&(y as isize)) { 2 } else { 1 };
 
-        let mut all_empty = true;
+        for _ in 0..copies {
+            for (x, el) in row.iter().enumerate() {
+                cells.push(*el);
 
-        for i in 0..width {
-            if galixy[row][i] != Empty {
-                all_empty = false;
-                break;
+                if empty_cols.contains(&(x as isize)) {
+                    cells.push(*el);
+                }
             }
-        }
 
-        if !all_empty {
-            row += 1;
-            continue;
+            height += 1;
         }
-
-        let new_row = vec![vec![Empty; width]];
-        galixy.splice(row..row, new_row);
-
-        row += 2;
-        height += 1;
     }
 
-    /* Add new columns */ 
-    loop {
-        if column >= width {
-            break;
-        }
-
-        let mut all_empty = true;
-
-        for i in 0..height {
-            if galixy[i][column] != Empty {
-                all_empty = false;
-                break;
-            }
-        }
-
-        if !all_empty {
-            column += 1;
-            continue;
-        }
-
-        for i in 0..height {
-            let new_element = vec![Empty];
-            galixy[i].splice(column..column, new_element);
-        }
-
-        column += 2;
-        width += 1;
-    }
+    Grid::new(galixy.width() + empty_cols.len(), height, cells)
 }
 
 
-fn get_empty(galixy: &Vec<Vec<Element>>) -> (Vec<u64>, Vec<u64>) {
-    let width = galixy[0].len();
-    let height = galixy.len();
-    let mut row = 0;
-    let mut column = 0;
-
-    let mut empty_rows = Vec::new();
-    let mut empty_cols = Vec::new();
-
-    /* Add new Rows */
-    loop {
-        if row >= height {
-            break;
-        }
-
-        let mut all_empty = true;
-
-        for i in 0..width {
-            if galixy[row][i] != Empty {
-                all_empty = false;
-                break;
-            }
-        }
-
-        if all_empty {
-            empty_rows.push(row as u64)
-        }
-
-        row += 1;
-    }
-
-    /* Add new columns */ 
-    loop {
-        if column >= width {
-            break;
-        }
-
-        let mut all_empty = true;
-
-        for i in 0..height {
-            if galixy[i][column] != Empty {
-                all_empty = false;
-                break;
-            }
-        }
+fn get_empty(galixy: &Grid<Element>) -> (Vec<isize>, Vec<isize>) {
+    let empty_rows = galixy.rows()
+        .enumerate()
+        .filter(|(_, row)| row.iter().all(|el| *el == Empty))
+        .map(|(y, _)| y as isize)
+        .collect();
 
-        if all_empty {
-            empty_cols.push(column as u64);
-        }
-
-        column += 1;
-    }
+    let empty_cols = galixy.columns()
+        .enumerate()
+        .filter_map(|(x, mut column)| column.all(|el| *el == Empty).then_some(x as isize))
+        .collect();
 
     (empty_rows, empty_cols)
 }
 
 
-fn parse_map(file_name: &str) -> Result<Vec<Vec<Element>>, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to read file");
-    };
-
-    let mut output = Vec::new();
-
-    for l in lines {
-        let Ok(line) = l else {
-            return Err("Failed to read line in file");
-        };
-
-        let mut row = Vec::new();
+fn parse_map<B: BufRead>(input: B) -> Result<Grid<Element>> {
+    Grid::parse(input, |ch| match ch {
+        '.' => Some(Empty),
+        '#' => Some(Galxiy),
+        _ => None
+    })
+}
 
-        for ch in line.chars() {
-            match ch {
-                '.' => row.push(Empty),
-                '#' => row.push(Galxiy),
-                _ => return Err("Invalid charicter found in input")
-            };
-        }
 
-        output.push(row);
+fn _print_galixy(map: &Grid<Element>) {
+    for row in map.rows() {
+        debug!("{}", row.iter().map(|el| match el {
+            Empty => '.',
+            Galxiy => '#',
+        }).collect::<String>());
     }
-
-    Ok(output)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::Day11;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day11, EXAMPLE, P1), 374.into());
+    }
 
-fn _print_galixy(map: &Vec<Vec<Element>>) {
-
-    for row in map {
-        for el in row {
-            match el {
-                Empty => print!("."),
-                Galxiy => print!("#"),
-            };
-        }
-
-        println!();
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day11, EXAMPLE, P2), 82000210.into());
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,10 @@
-use crate::days::Part;
-use crate::days::{read_lines, parse_num};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::days::parse_num;
+use crate::log::info;
+
+use std::io::BufRead;
 
 use self::Element::*;
 
@@ -11,30 +16,43 @@ enum Element {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct Input {
+pub struct Input {
     elements: Vec<Element>,
     required_gears: Vec<u64>,
     amount_to_touch: usize
 }
 
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Input>;
+
+    const DAY: u32 = 12;
+    const TITLE: &'static str = "Hot Springs";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_inputs(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
     }
 }
 
 
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let inputs = parse_inputs(file_name)?;
+fn part1(inputs: &[Input]) -> Result<u64> {
     let mut output = 0;
 
     for input in inputs {
-        let ways = count_ways(&input);
+        let ways = count_ways(input);
 
-        println!("  - {}", ways);
+        info!("  - {}", ways);
 
         output += ways;
     }
@@ -44,15 +62,14 @@ fn part1(file_name: &str) -> Result<u64, &'static str> {
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let inputs = parse_inputs(file_name)?;
-    let inputs = unwrap_inputs(&inputs);
+fn part2(inputs: &[Input]) -> Result<u64> {
+    let inputs = unwrap_inputs(inputs);
     let mut output = 0;
 
-    for input in inputs {
-        let ways = count_ways(&input);
+    for input in &inputs {
+        let ways = count_ways(input);
 
-        println!("  - {}", ways);
+        info!("  - {}", ways);
 
         output += ways;
     }
@@ -75,7 +92,7 @@ fn count_ways(input: &Input) -> u64 {
     }
 
     // Too high 7402
-    solve(&mut dp, input, 0, 0, 0) as u64
+    solve(&mut dp, input, 0, 0, 0)
 }
 
 fn solve(
@@ -83,7 +100,7 @@ fn solve(
         start_pos: usize, amount_touched: usize
     ) -> u64 {
     // dp[gear][start_pos] = number of ways to place gear starting at start_pos 
-    if dp[gear][start_pos][amount_touched] != None {
+    if dp[gear][start_pos][amount_touched].is_some() {
         return dp[gear][start_pos][amount_touched].unwrap();        
     }
 
@@ -115,7 +132,7 @@ fn solve(
         } 
 
         // dp, is enough space, can place this gear, check all other gearss
-        if dp[gear + 1][end_pos][amount_touched + current_touched] != None {
+        if dp[gear + 1][end_pos][amount_touched + current_touched].is_some() {
             ways += dp[gear + 1][end_pos][amount_touched + current_touched].unwrap();
             continue;
         }
@@ -131,7 +148,7 @@ fn solve(
 
     dp[gear][start_pos][amount_touched] = Some(ways);
 
-    return ways;
+    ways
 }
 
 fn can_place(
@@ -165,17 +182,13 @@ fn can_place(
 }
 
 
-fn parse_inputs(file_name: &str) -> Result<Vec<Input>, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to read input file");
-    };
+fn parse_inputs<B: BufRead>(input: B) -> Result<Vec<Input>> {
+    let lines = input.lines();
 
     let mut output = Vec::new();
 
-    for l in lines {
-        let Ok(line) = l else {
-            return Err("Failed to read line in input file");
-        };
+    for (i, l) in lines.enumerate() {
+        let line = l?;
 
         let mut elements = Vec::new();
         let mut required_gears = Vec::new();
@@ -192,17 +205,15 @@ fn parse_inputs(file_name: &str) -> Result<Vec<Input>, &'static str> {
                     Gear
                 },
                 Some(' ') => break,
-                _ => return Err("Invalid char found in input")
+                _ => return Err(Error::parse(
+                    i + 1, elements.len() + 1, &line, "Invalid char found in input"
+                ))
             };
 
             elements.push(element);
         }
 
-        loop {
-            let Some(n) = parse_num(&mut chars, true) else {
-                break;
-            };
-
+        while let Some(n) = parse_num(&mut chars, true) {
             required_gears.push(n as u64);
         }
 
@@ -215,7 +226,7 @@ fn parse_inputs(file_name: &str) -> Result<Vec<Input>, &'static str> {
 }
 
 
-fn unwrap_inputs(inputs: &Vec<Input>) -> Vec<Input> {
+fn unwrap_inputs(inputs: &[Input]) -> Vec<Input> {
     let mut output = Vec::new();
     let multiuply_amount = 5;
 
@@ -244,4 +255,30 @@ fn unwrap_inputs(inputs: &Vec<Input>) -> Vec<Input> {
     }
 
     output
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day12;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day12, EXAMPLE, P1), 21.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day12, EXAMPLE, P2), 525152.into());
+    }
+}
@@ -1,5 +1,10 @@
-use crate::days::Part;
-use crate::days::{read_lines};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::grid::Grid;
+use crate::log::info;
+
+use std::io::BufRead;
 
 use self::Element::*;
 use self::MirrorPoint::*;
@@ -17,25 +22,38 @@ enum MirrorPoint {
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     rows: Vec<u64>,
     cols: Vec<u64>
 }
 
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name, false),
-        Part::P2 => part1(file_name, true),
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<Input>;
+
+    const DAY: u32 = 13;
+    const TITLE: &'static str = "Point of Incidence";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input, false).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input, true).map(Answer::from)
     }
 }
 
 
-fn part1(file_name: &str, use_smudge: bool) -> Result<u64, &'static str> {
-    let inputs = parse_input(file_name)?;
+fn part1(inputs: &[Input], use_smudge: bool) -> Result<u64> {
     let mut score = 0;
 
-    for input in &inputs {
+    for (i, input) in inputs.iter().enumerate() {
         let mirrow_point = find_mirror_points(input, use_smudge);
         // Too high 37240
         //          36015
@@ -43,12 +61,14 @@ fn part1(file_name: &str, use_smudge: bool) -> Result<u64, &'static str> {
         let value = match mirrow_point {
             Some((Row, n)) => n as u64 * 100,
             Some((Col, n)) => n as u64,
-            None => panic!()
+            None => return Err(Error::unsolvable(
+                format!("No mirror line found in pattern {}", i + 1)
+            ))
         };
 
         score += value;
 
-        println!("{}", value);
+        info!("{}", value);
     }   
 
     Ok(score)
@@ -71,7 +91,7 @@ fn find_mirror_points(input: &Input, use_smudge: bool) -> Option<(MirrorPoint, u
 }
 
 
-fn find_mirror_point(list: &Vec<u64>, use_smudge: bool) -> Option<usize> {
+fn find_mirror_point(list: &[u64], use_smudge: bool) -> Option<usize> {
     let mut point = 0;
     let mut point_size = 0;
 
@@ -107,10 +127,7 @@ fn find_mirror_point(list: &Vec<u64>, use_smudge: bool) -> Option<usize> {
             }
         }
 
-        if is_perfect && !use_smudge {
-            point = i;
-            point_size = 1;
-        } else if used_smudge && is_perfect {
+        if is_perfect && (!use_smudge || used_smudge) {
             point = i;
             point_size = 1;
         }
@@ -129,77 +146,87 @@ fn power_of_two(x: u64) -> bool {
 }
 
 
-fn parse_input(file_name: &str) -> Result<Vec<Input>, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to read input file");
-    };
-
-    let mut elements = Vec::new();
+fn parse_input<B: BufRead>(input: B) -> Result<Vec<Input>> {
+    let mut block = Vec::new();
     let mut inputs = Vec::new();
 
-    for l in lines {
-        let Ok(line) = l else {
-            return Err("Failed to read line in input file");
-        };
+    for (i, l) in input.lines().enumerate() {
+        let line = l?;
 
-        if line.len() == 0 {
-            inputs.push(input_from_elements(&elements));
-            elements.clear();
+        if line.is_empty() {
+            inputs.push(parse_pattern(&block)?);
+            block.clear();
             continue;
         }
 
-        let mut row = Vec::new();
-
-        for ch in line.chars() {
-            match ch {
-                '.' => row.push(Empty),
-                '#' => row.push(Rock),
-                _ => return Err("Invalid input char")
-            };
-        }
-
-        elements.push(row)
+        block.push((i, line));
     }
 
-    inputs.push(input_from_elements(&elements));
+    if !block.is_empty() {
+        inputs.push(parse_pattern(&block)?);
+    }
 
     Ok(inputs)
 }
 
 
-fn input_from_elements(elements: &Vec<Vec<Element>>) -> Input {
-    let mut rows = Vec::new();
-    let mut cols = Vec::new();
+fn parse_pattern(block: &[(usize, String)]) -> Result<Input> {
+    let grid = Grid::from_lines(block.iter().map(|(i, line)| (*i, line)), |ch| match ch {
+        '.' => Some(Empty),
+        '#' => Some(Rock),
+        _ => None
+    })?;
 
-    for r in 0..elements.len() {
-        let mut id = 0;
+    Ok(input_from_grid(&grid))
+}
 
-        for i in 0..elements[0].len() {
-            id = (id << 1) + match elements[r][i] {
-                Empty => 0,
-                Rock => 1
-            };
-        }
 
-        rows.push(id);
+fn input_from_grid(grid: &Grid<Element>) -> Input {
+    Input {
+        rows: grid.rows().map(row_id).collect(),
+        cols: grid.transpose().rows().map(row_id).collect()
     }
+}
 
 
-    for c in 0..elements[0].len() {
-        let mut id = 0;
-
-        for i in 0..elements.len() {
-            id = (id << 1) + match elements[i][c] {
-                Empty => 0,
-                Rock => 1
-            };
-        }
+fn row_id(row: &[Element]) -> u64 {
+    row.iter().fold(0, |id, el| (id << 1) + match el {
+        Empty => 0,
+        Rock => 1
+    })
+}
 
-        cols.push(id);
+#[cfg(test)]
+mod tests {
+    use super::Day13;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day13, EXAMPLE, P1), 405.into());
     }
 
-    Input {
-        rows,
-        cols
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day13, EXAMPLE, P2), 400.into());
     }
-}
\ No newline at end of file
+}
@@ -1,48 +1,62 @@
-use crate::days::Part;
-use crate::days::{read_lines};
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::geometry::Direction::{self, North, East, South, West};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::log::{info, debug};
+use std::io::BufRead;
 use std::fmt;
 use std::collections::HashMap;
 
 use self::Element::*;
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Element {
+pub enum Element {
     Empty,
     Rock,
     Wall
 }
 
-#[derive(Debug)]
-struct Input {
-    elements: Vec<Element>,
-    width: usize,
-    height: usize
-}
+type Input = Grid<Element>;
+
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Input;
+
+    const DAY: u32 = 14;
+    const TITLE: &'static str = "Parabolic Reflector Dish";
 
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
     }
 }
 
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let mut input= parse_input(file_name)?;
+fn part1(input: &Input) -> Result<u64> {
+    let mut input = input.clone();
 
-    println!("{}", input);
+    debug!("{}", input);
 
-    input.slide_north();
+    slide_north(&mut input);
 
-    println!("{}", input);
+    debug!("{}", input);
 
     Ok(count_score(&input))
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let mut input= parse_input(file_name)?;
+fn part2(input: &Input) -> Result<u64> {
+    let mut input = input.clone();
     let mut map = HashMap::new();
 
     let mut i: i32 = 0;
@@ -58,8 +72,8 @@ fn part2(file_name: &str) -> Result<u64, &'static str> {
         }
 
         perform_cycle(&mut input);
-        let hash = input.hash();
-        println!("{} {}", i, count_score(&input));
+        let hash = input.to_string();
+        debug!("{} {}", i, count_score(&input));
 
         if map.contains_key(&hash) && !jumped {
             let i_at_prev = map.get(&hash).unwrap();
@@ -67,7 +81,7 @@ fn part2(file_name: &str) -> Result<u64, &'static str> {
             let max_increase = max - i;
             let increase = (max_increase / diff - 1) * diff + 1;
 
-            println!("{} {} {} {}", i, i_at_prev, diff, increase);
+            info!("{} {} {} {}", i, i_at_prev, diff, increase);
 
             if increase <= 0 {
                 i += 1;
@@ -90,229 +104,69 @@ fn part2(file_name: &str) -> Result<u64, &'static str> {
 
 
 fn perform_cycle(input: &mut Input) {
-    // Slide North 
-    input.slide_north();
+    for dir in [North, West, South, East] {
+        tilt(input, dir);
+    }
+}
 
-    input.rotate();
-    input.slide_north();
-    // Slide West
 
-    input.rotate();
-    input.slide_north();
-    // Slide South
+/* Turns the platform until dir faces north, slides, then turns it back */
+fn tilt(input: &mut Input, dir: Direction) {
+    let turns = Direction::CARDINAL.iter().position(|&d| d == dir).expect("tilting towards a side");
 
-    input.rotate();
-    input.slide_north();
-    // Slide East
+    for _ in 0..turns {
+        *input = input.rotate_counter_clockwise();
+    }
+
+    slide_north(input);
 
-    input.rotate();
-    // Face North Again 
+    for _ in 0..turns {
+        *input = input.rotate_clockwise();
+    }
 }
 
 
 fn count_score(input: &Input) -> u64 {
     let mut score = 0;
 
+    for (y, row) in input.rows().enumerate() {
+        let height_score = (input.height() - y) as u64;
 
-    for y in 0..input.height {
-        let height_score = (input.height - y) as u64;
-
-        for x in 0..input.width {
-            if input.get(x, y) != Some(&Rock) {
-                continue;
-            }
-
-            score += height_score;
-        }
+        score += row.iter().filter(|el| **el == Rock).count() as u64 * height_score;
     }
 
-
     score
 }
 
 
-impl Input {
-    fn get(&self, x: usize, y: usize) -> Option<&Element> {
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-
-        Some(&self.elements[(y * self.width) + x])
-    }
-
-    fn set(&mut self, x: usize, y: usize, element: Element) {
-        if x >= self.width || y >= self.height {
-            return;
-        }
-
-        self.elements[(y * self.width) + x] = element;
-    }
-
-    fn replace(&mut self, x1: usize, y1: usize, x2: usize, y2: usize) {
-        if x1 >= self.width || y1 >= self.height || 
-            x2 >= self.width || y2 >= self.height {
-            return;
-        }
-
-        self.elements[(y1 * self.width) + x1] = 
-            self.elements[(y2 * self.width) + x2];
-    }
+fn slide_north(input: &mut Input) {
+    for x in 0..input.width() {
+        let mut tail = 0; // Place to put the next rock
 
-
-    fn slide_north(&mut self) {
-        let mut head = Vec::new(); // Stores top searching postion 
-        let mut tail = Vec::new(); // Stores place to put element 
-
-        if self.height == 1 {
-            return;
-        }
-
-        for _x in 0..self.width {
-            head.push(1);
-            tail.push(0);
-        }
-
-        loop {
-            let mut all_ended = true;
-            // print!("{}\n\n", self);
-            for (x, y) in head.iter().enumerate() {
-                let y = *y;
-                let Some(element) = self.get(x, y) else {
-                    continue;
-                };
-
-                all_ended = false;
-
-                match element {
-                    Wall => {
-                        tail[x] = y + 1;
-                        continue;
-                    }, // New tail
-                    Empty => continue, // Do nothing 
-                    Rock => (), // Need to push rock down
-                };
-
-
-                for new_y in tail[x]..(y + 1) {
-                    match self.get(x, new_y).unwrap() {
-                        Empty => {
-                            // Place rock here 
-                            self.set(x, y, Empty);
-                            self.set(x, new_y, Rock);
-                            tail[x] = new_y;
-                            break;
-                        },
-                        Rock | Wall => {
-                            tail[x] = new_y;
-                        }
-                    }
+        for y in 0..input.height() {
+            match input[(x, y)] {
+                Wall => tail = y + 1,
+                Empty => (),
+                Rock => {
+                    input.swap((x, y), (x, tail));
+                    tail += 1;
                 }
             }
-
-
-            if all_ended {
-                break;
-            }
-
-            for x in 0..head.len() {
-                head[x] += 1;
-            }
-        }
-    }
-
-
-    fn rotate(&mut self) {
-        if self.width != self.height {
-            panic!();
-        }
-
-        let size = self.width;
-
-        for i in 0..(size / 2) {
-            for j in i..(size - i - 1) {
-                let tmp = *self.get(j, i).unwrap();
-                self.replace(
-                    j, i, i, size - 1 - j
-                );
-                self.replace(
-                    i, size - 1 - j, size - 1 - j, size - 1 - i
-                );
-                self.replace(
-                    size - 1 - j, size - 1 - i, size - 1 - i, j
-                );
-                self.set(size - 1 - i, j, tmp);
-            }
         }
     }
-
-
-    fn hash(&self) -> String {
-        let mut output = String::new();
-
-        for i in 0..self.elements.len() {
-            output.push(match self.elements[i] {
-                Empty => '.',
-                Wall => '#',
-                Rock => 'O'
-            });
-        }
-
-        output
-    }
 }
 
 
-fn parse_input(file_name: &str) -> Result<Input, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to read input file");
-    };
-
-    let mut elements = Vec::new();
-    let mut width = 0;
-    let mut height = 0;
-
-    for l in lines {
-        let Ok(line) = l else {
-            return Err("Failed to read line in input file");
-        };
-
-        for ch in line.chars() {
-            match ch {
-                '.' => elements.push(Empty),
-                '#' => elements.push(Wall),
-                'O' => elements.push(Rock),
-                _ => return Err("Invalid input char")
-            };
-        }
-
-        if width == 0 {
-            width = elements.len();
-        }
-        height += 1;
-    }
-
-    Ok(Input {
-        elements,
-        width,
-        height
+fn parse_input<B: BufRead>(input: B) -> Result<Input> {
+    Grid::parse(input, |ch| match ch {
+        '.' => Some(Empty),
+        '#' => Some(Wall),
+        'O' => Some(Rock),
+        _ => None
     })
 }
 
 
-impl fmt::Display for Input {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                write!(f, "{}", self.get(x, y).unwrap())?;
-            }
-            write!(f, "\n")?;
-        }
-        fmt::Result::Ok(())
-    }
-}
-
-
-
 impl fmt::Display for Element {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -321,4 +175,34 @@ impl fmt::Display for Element {
             Empty => write!(f, ".")
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day14;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day14, EXAMPLE, P1), 136.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day14, EXAMPLE, P2), 64.into());
+    }
+}
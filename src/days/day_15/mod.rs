@@ -1,15 +1,34 @@
+use std::io::BufRead;
 use std::mem::{MaybeUninit, transmute};
-use std::usize;
 
-use crate::days::Part;
-use crate::days::{read_lines};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
 
 use self::Operation::*;
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = String;
+
+    const DAY: u32 = 15;
+    const TITLE: &'static str = "Lens Library";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        let Some(line) = input.lines().next() else {
+            return Err(Error::parse(1, 1, "", "Missing initialization sequence"));
+        };
+
+        Ok(line?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
     }
 }
 
@@ -27,15 +46,7 @@ struct Command<'a> {
 }
 
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let Ok(mut lines) = read_lines(file_name) else {
-        return Err("Failed to read file");
-    };
-
-    let Some(Ok(line)) = lines.next() else {
-        return Err("Failed to read line");
-    };
-
+fn part1(line: &str) -> Result<u64> {
     let score = line
         .split(',').fold(0, |acc, s| acc + hash(s) as u64);
 
@@ -43,17 +54,9 @@ fn part1(file_name: &str) -> Result<u64, &'static str> {
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
+fn part2(line: &str) -> Result<u64> {
     const LENGTH: usize = 256;
 
-    let Ok(mut lines) = read_lines(file_name) else {
-        return Err("Failed to read file");
-    };
-
-    let Some(Ok(line)) = lines.next() else {
-        return Err("Failed to read line");
-    };
-
     let mut maps: [Vec<Command>; LENGTH] = {
         let mut data: [MaybeUninit<Vec<Command>>; LENGTH] = unsafe {
             MaybeUninit::uninit().assume_init()
@@ -66,11 +69,15 @@ fn part2(file_name: &str) -> Result<u64, &'static str> {
         unsafe { transmute::<_, [Vec<Command>; LENGTH]>(data)}
     };
 
+    let mut column = 1;
+
     for step in line.split(',') {
         let Some(command) = get_command(step) else {
-            return Err("Failed to aprse command");
+            return Err(Error::parse(1, column, line, "Failed to parse command"));
         };
 
+        column += step.len() + 1;
+
         let hash = hash(command.id) as usize;
 
         match command.opp {
@@ -90,8 +97,8 @@ fn part2(file_name: &str) -> Result<u64, &'static str> {
         .map(|i| maps[i]
             .iter().enumerate()
             .map(|(j, c)| (i + 1) as u64 * (j + 1) as u64 * c.val as u64)
-            .fold(0, |acc, s| acc + s)
-        ).fold(0, |acc, s| acc + s);
+            .sum::<u64>()
+        ).sum::<u64>();
 
     Ok(score)
 }
@@ -99,7 +106,7 @@ fn part2(file_name: &str) -> Result<u64, &'static str> {
 
 impl PartialEq for Command<'_> {
     fn eq(&self, other: &Self) -> bool {
-        return self.id == other.id
+        self.id == other.id
     }
 }
 
@@ -124,15 +131,11 @@ fn get_command<'a>(input: &'a str) -> Option<Command<'a>> {
         }
     }
 
-    if opp == None {
-        None
-    } else {
-        Some(Command {
-            opp: opp.unwrap(),
-            id: &input[..(str_end + 1)],
-            val,
-        })
-    }
+    opp.map(|opp| Command {
+        opp,
+        id: &input[..(str_end + 1)],
+        val,
+    })
 }
 
 
@@ -140,5 +143,26 @@ fn get_command<'a>(input: &'a str) -> Option<Command<'a>> {
 fn hash(input: &str) -> u32 {
     input
         .chars()
-        .fold(0, |acc, x| ((acc as u32 + x as u32) * 17) % 256)
-}
\ No newline at end of file
+        .fold(0, |acc, x| ((acc + x as u32) * 17) % 256)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day15;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day15, EXAMPLE, P1), 1320.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day15, EXAMPLE, P2), 145.into());
+    }
+}
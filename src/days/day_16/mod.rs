@@ -1,17 +1,34 @@
-use std::usize;
+use std::io::BufRead;
 use std::fmt;
 use std::cmp::max;
 
-use crate::days::Part;
-use crate::days::{read_lines};
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::geometry::Direction;
+use crate::geometry::Direction::*;
+use crate::answer::Answer;
+use crate::solution::Solution;
 
 use self::Element::*;
-use self::Direction::*;
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Input;
+
+    const DAY: u32 = 16;
+    const TITLE: &'static str = "The Floor Will Be Lava";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
     }
 }
 
@@ -24,52 +41,42 @@ enum Element {
     HotizontalSpliter
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
-enum Direction {
-    North,
-    South,
-    East,
-    West
+#[derive(Debug, Clone)]
+pub struct Input {
+    elements: Grid<Element>,
+    beams: Grid<Option<Vec<Direction>>>
 }
 
-#[derive(Debug)]
-struct Input {
-    elements: Vec<Element>,
-    beams: Vec<Option<Vec<Direction>>>,
-    width: usize,
-    height: usize
-}
+type Pos = (usize, usize);
 
-type InputSize = usize;
-
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let mut input = parse_input(file_name)?;
+fn part1(input: &Input) -> Result<u64> {
+    let mut input = input.clone();
 
     Ok(calculate_covered(&mut input, 0, 0, East))
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let mut input = parse_input(file_name)?;
+fn part2(input: &Input) -> Result<u64> {
+    let mut input = input.clone();
     let mut max_covered = 0;
 
-    for x in 0..input.width {
+    for x in 0..input.elements.width() {
         let covered = calculate_covered(&mut input, x, 0, South);
         input.reset_beams();
         max_covered = max(covered, max_covered);
 
-        let y = input.height - 1;
+        let y = input.elements.height() - 1;
         let covered = calculate_covered(&mut input, x, y, North);
         input.reset_beams();
         max_covered = max(covered, max_covered);
     }
 
-    for y in 0..input.height {
+    for y in 0..input.elements.height() {
         let covered = calculate_covered(&mut input, 0, y, East);
         input.reset_beams();
         max_covered = max(covered, max_covered);
 
-        let x = input.width - 1;
+        let x = input.elements.width() - 1;
         let covered = calculate_covered(&mut input, x, y, West);
         input.reset_beams();
         max_covered = max(covered, max_covered);
@@ -81,7 +88,7 @@ fn part2(file_name: &str) -> Result<u64, &'static str> {
 
 fn calculate_covered(input: &mut Input, x: usize, y: usize, start_dir: Direction) -> u64 {
     let mut beam_heads = Vec::new();
-    let start_pos = input.get_index(x, y).unwrap();
+    let start_pos = (x, y);
 
     for dir in input.new_beam_heads(start_pos, start_dir) {
         input.add_beam(start_dir, start_pos);
@@ -89,25 +96,17 @@ fn calculate_covered(input: &mut Input, x: usize, y: usize, start_dir: Direction
     }
 
     loop {
-        if beam_heads.len() == 0 {
+        if beam_heads.is_empty() {
             break;
         }
 
         let mut new_beam_heads = Vec::new();
 
         for (x, y, dir) in beam_heads {
-            let (dx, dy) = match dir {
-                North => (0, -1),
-                South => (0, 1),
-                East => (1, 0),
-                West => (-1, 0)
-            };
-
-            let Some(new_pos) = input.try_get_index(x, y, dx, dy) else {
+            let Some(new_pos) = input.elements.step(x, y, dir) else {
                 continue;
             };
-            let nx = x.checked_add_signed(dx).unwrap();
-            let ny = y.checked_add_signed(dy).unwrap();
+            let (nx, ny) = new_pos;
             let dirs = input.new_beam_heads(new_pos, dir);
 
             for dir in dirs {
@@ -126,81 +125,38 @@ fn calculate_covered(input: &mut Input, x: usize, y: usize, start_dir: Direction
     // println!("{}", input);
 
     input.beams.iter()
-        .map(|s| if s.is_some() {1} else {0})
-        .reduce(|acc, x| acc + x).unwrap()
+        .filter(|(_, beams)| beams.is_some())
+        .count() as u64
 }
 
 
 impl Input {
-    fn add_beam(&mut self, dir: Direction, i: InputSize) -> bool {
-        if self.beams[i] == None {
-            self.beams[i] = Some(vec![dir]);
-            return true; 
-        }
-
-        let beams = self.beams.get_mut(i).unwrap().as_mut().unwrap();
+    fn add_beam(&mut self, dir: Direction, pos: Pos) -> bool {
+        let beams = self.beams[pos].get_or_insert_with(Vec::new);
 
         if beams.contains(&dir) {
             return false;
         }
 
         beams.push(dir);
-        return true;
-    }
-
-    fn get_index(&self, x: usize, y: usize) -> Option<InputSize> {
-        if x >= self.width || y >= self.height {
-            None
-        } else {
-            Some((y * self.width) + x)
-        }
-    }
-
-    fn try_get_index(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<InputSize> {
-        let nx = x.checked_add_signed(dx);
-        let ny = y.checked_add_signed(dy);
-
-        match (nx, ny) {
-            (Some(nx), Some(ny)) => self.get_index(nx, ny),
-            _ => None
-        }
+        true
     }
 
     fn reset_beams(&mut self) {
-        for i in 0..self.beams.len() {
-            self.beams[i] = None;
-        }
+        self.beams = Grid::filled(self.elements.width(), self.elements.height(), None);
     }
 
-    fn new_beam_heads(&self, pos: InputSize, dir: Direction) -> Vec<Direction> {
-        match (&self.elements[pos], &dir) {
-            (RightMirror, East) | 
-            (LeftMirror, West) => {
-                vec![North]
-            },
-            (RightMirror, West) |
-            (LeftMirror, East) => {
-                vec![South]
-            },
-            (RightMirror, North) | 
-            (LeftMirror, South) => {
-                vec![East]
-            },
-            (RightMirror, South) | 
-            (LeftMirror, North) => {
-                vec![West]
-            },
-            (HotizontalSpliter, South) | 
-            (HotizontalSpliter, North) => {
-                vec![East, West]
-            },
-            (VertialSpliter, East) |
-            (VertialSpliter, West) => {
-                vec![North, South]
-            }
-            (_, _) => {
-                vec![dir]
-            }
+    fn new_beam_heads(&self, pos: Pos, dir: Direction) -> Vec<Direction> {
+        let horizontal = dir == East || dir == West;
+
+        match self.elements[pos] {
+            RightMirror if horizontal => vec![dir.turn_left()],
+            RightMirror => vec![dir.turn_right()],
+            LeftMirror if horizontal => vec![dir.turn_right()],
+            LeftMirror => vec![dir.turn_left()],
+            HotizontalSpliter if !horizontal => vec![dir.turn_left(), dir.turn_right()],
+            VertialSpliter if horizontal => vec![dir.turn_left(), dir.turn_right()],
+            _ => vec![dir]
         }
     }
 
@@ -209,9 +165,9 @@ impl Input {
 
 impl fmt::Display for Input {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let i = self.get_index(x, y).unwrap();
+        for y in 0..self.elements.height() {
+            for x in 0..self.elements.width() {
+                let i = (x, y);
 
                 match &self.beams[i] {
                     Some(beams) if 
@@ -219,20 +175,13 @@ impl fmt::Display for Input {
                         if beams.len() > 1 {
                             write!(f, "{}", beams.len())?;
                         } else {
-                            let ch = match &beams[0] {
-                                North => '^',
-                                East => '>',
-                                South => 'v',
-                                West => '<',
-                            };
-
-                            write!(f, "{}", ch)?;
+                            write!(f, "{}", beams[0].arrow())?;
                         }
                     },
                     _ => write!(f, "{}", self.elements[i])?,
                 }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         fmt::Result::Ok(())
     }
@@ -257,46 +206,48 @@ impl fmt::Display for Element {
 
 
 
-fn parse_input(file_name: &str) -> Result<Input, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to open file");
-    };
-
-    let mut width = 0;
-    let mut height = 0;
-    let mut elements = Vec::new();
-    let mut beams = Vec::new();
-
-    for l in lines {
-        let Ok(line) = l else {
-            return Err("Failed to read line in file");
-        };
-
-        for c in line.chars() {
-            let element = match c {
-                '|' => VertialSpliter,
-                '-' => HotizontalSpliter,
-                '/' => RightMirror,
-                '\\' => LeftMirror,
-                '.' => Empty,
-                _ => return Err("Invalid char found in input")
-            };
-
-            elements.push(element);
-            beams.push(None);
-        }
-
-        if width == 0 {
-            width = elements.len();
-        }
-
-        height += 1;
-    }
+fn parse_input<B: BufRead>(input: B) -> Result<Input> {
+    let elements = Grid::parse(input, |c| match c {
+        '|' => Some(VertialSpliter),
+        '-' => Some(HotizontalSpliter),
+        '/' => Some(RightMirror),
+        '\\' => Some(LeftMirror),
+        '.' => Some(Empty),
+        _ => None
+    })?;
+    let beams = Grid::filled(elements.width(), elements.height(), None);
 
     Ok(Input {
         elements,
-        width,
-        height,
         beams
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day16;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day16, EXAMPLE, P1), 46.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day16, EXAMPLE, P2), 51.into());
+    }
+}
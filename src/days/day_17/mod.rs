@@ -1,228 +1,338 @@
-use crate::days::Part;
-use crate::days::{read_lines};
-
-use std::collections::{HashMap, BinaryHeap};
-use std::slice::Iter;
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::grid::Grid;
+use crate::geometry::{Direction, Point};
+use crate::search;
+use crate::log::{debug, trace};
+
+use std::io::BufRead;
 use std::fmt;
 
-use self::Direction::*;
+use colored::Colorize;
 
-#[derive(Debug)]
-struct Input {
-    nodes: Vec<Node>,
-    width: usize,
-    height: usize
+
+/* Heat loss of each city block */
+type Input = Grid<u8>;
+
+
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Input;
+
+    const DAY: u32 = 17;
+    const TITLE: &'static str = "Clumsy Crucible";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
-type InputSize = usize;
+/* Block the crucible stands on and the direction it moved to get there */
+type State = ((usize, usize), Option<Direction>);
 
 #[derive(Debug)]
-struct Node {
-    heat_loss: u8
+struct Route {
+    start: (usize, usize),
+    heat_loss: u64,
+    /* Every block the crucible enters after the start, with its heading */
+    blocks: Vec<((usize, usize), Direction)>
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-    Void
+/* How the crucible may move from block to block */
+#[derive(Debug, Clone)]
+struct Rules {
+    min_step: usize,
+    max_step: usize,
+    /* May turn straight back the way it came */
+    reversal: bool,
+    diagonals: bool,
+    /* Extra heat loss each time the crucible changes direction */
+    turn_penalty: u64
 }
 
+/* A city block drawn with the route over it */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    Off(u8),
+    On(u8),
+    Turn(u8)
+}
+
+
+impl Rules {
+    const CRUCIBLE: Rules = Rules::straight(1, 3);
+    const ULTRA_CRUCIBLE: Rules = Rules::straight(4, 10);
+
+    /* Only turns left or right, every min_step to max_step blocks */
+    const fn straight(min_step: usize, max_step: usize) -> Rules {
+        Rules { min_step, max_step, reversal: false, diagonals: false, turn_penalty: 0 }
+    }
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
+    fn directions(&self) -> &'static [Direction] {
+        if self.diagonals {
+            &Direction::ALL
+        } else {
+            &Direction::CARDINAL
+        }
     }
 }
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let input = parse_input(file_name)?;
 
-    println!("{}", input);
+fn part1(input: &Input) -> Result<u64> {
+    debug!("{}", input);
     
-    Ok(dijkstra(&input, 1, 3))
+    least_heat_loss(input, &Rules::CRUCIBLE)
 }
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let input = parse_input(file_name)?;
+fn part2(input: &Input) -> Result<u64> {
+    least_heat_loss(input, &Rules::ULTRA_CRUCIBLE)
+}
 
-    // println!("{}", input);
-    
-    Ok(dijkstra(&input, 4, 10))
+
+/* From the lava pool in the top left to the factory in the bottom right */
+fn least_heat_loss(input: &Input, rules: &Rules) -> Result<u64> {
+    let factory = (input.width() - 1, input.height() - 1);
+
+    let Some(route) = route(input, (0, 0), &[factory], rules)? else {
+        return Err(Error::unsolvable("No route to the factory"));
+    };
+
+    trace!("{:?}", route.blocks);
+    debug!("{}", route.render(input));
+
+    Ok(route.heat_loss)
 }
 
 
-fn dijkstra(input: &Input, min_step: usize, max_step: usize) -> u64 {
-    let mut dists = HashMap::new();
-    let mut queue = BinaryHeap::new();
+/*
+ * The cheapest route from start to whichever goal is cheapest to reach,
+ * or None if the rules never let the crucible get there.
+ */
+fn route(input: &Input, start: (usize, usize), goals: &[(usize, usize)], rules: &Rules) -> Result<Option<Route>> {
+    /* Standing still would make every state its own neighbour */
+    if rules.min_step == 0 {
+        return Err(Error::invalid_state("A crucible has to move at least one block at a time"));
+    }
 
-    let start_pos = (0, 0);
-    let end_pos = (input.width, input.height);
+    if input.get(start.0, start.1).is_none() {
+        return Ok(None);
+    }
 
-    queue.push((0, (start_pos.0, start_pos.1, Void)));
+    /* Every block costs at least the cheapest one, so this never overestimates */
+    let Some(min_heat_loss) = input.iter().map(|(_, heat_loss)| *heat_loss as u64).min() else {
+        return Ok(None);
+    };
+    let heuristic = |&(pos, _): &State| {
+        let blocks = goals.iter().map(|&goal| {
+            let distance = Point::from(goal) - Point::from(pos);
+
+            if rules.diagonals {
+                distance.chebyshev()
+            } else {
+                distance.manhattan()
+            }
+        });
 
-    while let Some((cost, (x, y, dir))) = queue.pop() {
-        // println!("{} {} {:?}", x, y, dir);
-    
-        if x == end_pos.0 - 1 && y == end_pos.1 - 1 {
-            return (-cost) as u64;
-        }
+        blocks.min().unwrap_or(0) as u64 * min_heat_loss
+    };
 
-        if dists.get(&(x, y, dir)).is_some_and(|&c| -cost > c) {
-            continue;
-        }
+    let neighbors = |&((x, y), dir): &State| {
+        let mut moves = Vec::new();
 
-        for n_dir in Direction::iterator() {
-            if &dir == n_dir || &dir.invert() == n_dir{
+        for &n_dir in rules.directions() {
+            if dir.is_some_and(|dir| dir == n_dir || (dir.invert() == n_dir && !rules.reversal)) {
                 continue;
             }
 
-            let mut n_cost = -cost; // Need to store cost negated in queue, so invert to get true cost 
+            let mut n_cost = match dir {
+                Some(_) => rules.turn_penalty,
+                None => 0
+            };
 
-            for dist in 1..=max_step {
-                let Some((node, n_x, n_y)) = input.try_get_move(x, y, *n_dir, dist) else {
+            for dist in 1..=rules.max_step {
+                let Some(n_pos) = input.offset(x, y, n_dir.offset() * dist as isize) else {
                     break;
                 };
 
-                n_cost += node.heat_loss as i64;
-
-                if dist < min_step {
-                    continue;
-                }
-
-                let key = (n_x, n_y, *n_dir);
+                n_cost += input[n_pos] as u64;
 
-                if n_cost < *dists.get(&key).unwrap_or(&i64::MAX) {
-                    dists.insert(key, n_cost);
-                    queue.push((-n_cost, key));
+                if dist >= rules.min_step {
+                    moves.push(((n_pos, Some(n_dir)), n_cost));
                 }
             }
         }
-    }
 
-    unreachable!()
+        moves
+    };
+
+    let Some((heat_loss, path)) = search::astar((start, None), neighbors, heuristic, |(pos, _)| goals.contains(pos)) else {
+        return Ok(None);
+    };
+
+    Ok(Some(Route {
+        start,
+        heat_loss,
+        blocks: expand_path(input, &path)
+    }))
 }
 
 
-impl Direction {
-    fn iterator() -> Iter<'static, Direction> {
-        static DIRECTIONS: [Direction; 4] = [North, South, East, West];
+/* The search jumps several blocks per state, so fill in the blocks between */
+fn expand_path(input: &Input, path: &[State]) -> Vec<((usize, usize), Direction)> {
+    let mut blocks = Vec::new();
 
-        DIRECTIONS.iter()
-    }
+    for pair in path.windows(2) {
+        let (mut pos, _) = pair[0];
+        let (to, Some(dir)) = pair[1] else {
+            continue;
+        };
 
-    fn invert(&self) -> Direction {
-        match self {
-            North => South,
-            South => North,
-            East => West,
-            West => East,
-            Void => Void
+        while pos != to {
+            pos = input.step(pos.0, pos.1, dir).expect("route leaves the city");
+            blocks.push((pos, dir));
         }
     }
+
+    blocks
 }
 
 
-impl Input {
-    fn get<'a>(&'a self, i: InputSize) -> &'a Node {
-        &self.nodes[i]
-    }
+impl Route {
+    /* The heat loss map with the route highlighted, turns standing out most */
+    fn render(&self, input: &Input) -> Grid<Block> {
+        let mut grid = Grid::new(
+            input.width(), input.height(),
+            input.iter().map(|(_, heat_loss)| Block::Off(*heat_loss)).collect()
+        );
 
-    fn try_get<'a>(&'a self, x: usize, y: usize) -> Option<&'a Node> {
-       let i = self.try_get_index(x, y)?;
-       Some(self.get(i))
-    }
+        grid[self.start] = Block::On(input[self.start]);
 
-    fn try_get_move<'a>(&'a self, x: usize, y: usize, dir: Direction, dist: usize) -> Option<(&'a Node, usize, usize)> {
-        let (dx, dy) = match dir {
-            North => (0, -1),
-            South => (0, 1),
-            East => (1, 0),
-            West => (-1, 0),
-            Void => panic!(),
-        };
+        for (i, &(pos, dir)) in self.blocks.iter().enumerate() {
+            let turns = self.blocks.get(i + 1).is_some_and(|&(_, next)| next != dir);
 
-        let dx = dx * dist as isize;
-        let dy = dy * dist as isize;
-
-        let (nx, ny) = self.try_get_change(x, y, dx, dy)?; 
-        let i = self.try_get_index(nx, ny)?;
+            grid[pos] = if turns {
+                Block::Turn(input[pos])
+            } else {
+                Block::On(input[pos])
+            };
+        }
 
-        Some((self.get(i), nx, ny))
+        grid
     }
+}
 
-    fn try_get_index(&self, x: usize, y: usize) -> Option<InputSize> {
-        if x >= self.width || y >= self.height {
-            None
-        } else {
-            Some((y * self.width) + x)
+
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Block::Off(heat_loss) => write!(f, "{}", heat_loss.to_string().dimmed()),
+            Block::On(heat_loss) => write!(f, "{}", heat_loss.to_string().yellow().bold()),
+            Block::Turn(heat_loss) => write!(f, "{}", heat_loss.to_string().black().on_yellow()),
         }
     }
+}
 
-    fn try_get_change(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
-        let nx = x.checked_add_signed(dx)?;
-        let ny = y.checked_add_signed(dy)?;
 
-        self.try_get_index(nx, ny)?;
+fn parse_input<B: BufRead>(input: B) -> Result<Input> {
+    Grid::parse(input, |ch| ch.to_digit(10).map(|num| num as u8))
+}
 
-        Some((nx, ny))
+#[cfg(test)]
+mod tests {
+    use super::{Block, Day17, Rules, parse_input, route};
+    use crate::error::Error;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE_1: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533
+";
+
+    const EXAMPLE_2: &str = "\
+111111111111
+999999999991
+999999999991
+999999999991
+999999999991
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day17, EXAMPLE_1, P1), 102.into());
     }
 
-}
-
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day17, EXAMPLE_1, P2), 94.into());
+    }
 
-impl fmt::Display for Input {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                write!(f, "{}", self.try_get(x, y).unwrap().heat_loss)?;
-            }
-            write!(f, "\n")?;
-        }
-        fmt::Result::Ok(())
+    #[test]
+    fn part2_unfavourable_example() {
+        assert_eq!(solve_example(&Day17, EXAMPLE_2, P2), 71.into());
     }
-}
 
+    #[test]
+    fn ultra_route_matches_heat_loss() {
+        let input = parse_input(EXAMPLE_1.as_bytes()).unwrap();
+        let route = route(&input, (0, 0), &[(12, 12)], &Rules::ULTRA_CRUCIBLE).unwrap().unwrap();
 
-fn parse_input(file_name: &str) -> Result<Input, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to read file")
-    };
+        let total: u64 = route.blocks.iter().map(|&(pos, _)| input[pos] as u64).sum();
+        assert_eq!(total, route.heat_loss);
+        assert_eq!(route.blocks.last().map(|&(pos, _)| pos), Some((12, 12)));
 
-    let mut nodes = Vec::new();
-    let mut width = 0;
-    let mut height = 0;
+        /* Every straight run between turns is 4 to 10 blocks long */
+        let mut runs = route.blocks.chunk_by(|a, b| a.1 == b.1).map(|run| run.len());
+        assert!(runs.all(|len| (4..=10).contains(&len)));
+    }
 
-    for l in lines {
-        let Ok(line) = l else {
-            return Err("Failed to read line in input")
+    #[test]
+    fn routes_with_custom_rules() {
+        let input = parse_input("11111\n19991\n11111\n".as_bytes()).unwrap();
+        let cost = |start, goals: &[(usize, usize)], rules: Rules| {
+            route(&input, start, goals, &rules).unwrap().map(|route| route.heat_loss)
         };
+        let free = Rules { min_step: 1, max_step: 10, ..Rules::CRUCIBLE };
 
-        for ch in line.chars() {
-            if !('0'..='9').contains(&ch) {
-                return Err("Invalid char found in input");
-            }
+        assert_eq!(cost((0, 0), &[(4, 2)], free.clone()), Some(6));
+        assert_eq!(cost((0, 0), &[(4, 2), (2, 0)], free.clone()), Some(2));
+        assert_eq!(cost((0, 0), &[(4, 2)], Rules { diagonals: true, ..free.clone() }), Some(5));
+        assert_eq!(cost((0, 0), &[(4, 2)], Rules { turn_penalty: 10, ..free.clone() }), Some(16));
 
-            let num = ch as u8 - '0' as u8;
+        let grid = route(&input, (2, 0), &[(4, 2)], &free).unwrap().unwrap().render(&input);
+        assert_eq!((grid[(2, 0)], grid[(0, 0)]), (Block::On(1), Block::Off(1)));
 
-            nodes.push(Node {
-                heat_loss: num
-            });
-        }
+        /* Has to overshoot to the east end of a corridor before coming back west */
+        let corridor = parse_input("1111\n".as_bytes()).unwrap();
+        let back = Rules { min_step: 2, max_step: 4, ..Rules::CRUCIBLE };
+        assert!(route(&corridor, (1, 0), &[(0, 0)], &back).unwrap().is_none());
 
-        if width == 0 {
-            width = nodes.len();
-        }
+        let back = Rules { reversal: true, ..back };
+        assert_eq!(route(&corridor, (1, 0), &[(0, 0)], &back).unwrap().map(|route| route.heat_loss), Some(5));
 
-        height += 1;
+        let still = Rules { min_step: 0, ..back };
+        assert!(matches!(route(&corridor, (1, 0), &[(0, 0)], &still), Err(Error::InvalidState(_))));
     }
-
-    Ok(Input {
-        nodes,
-        width,
-        height
-    })
-}
\ No newline at end of file
+}
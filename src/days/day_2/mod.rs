@@ -1,11 +1,65 @@
-use crate::days::Part;
+use crate::days::collect_lines;
+use crate::error::Result;
+use crate::answer::Answer;
+use crate::solution::Solution;
+
+use std::io::BufRead;
 
 mod part1;
 mod part2;
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1::run(file_name),
-        Part::P2 => part2::run(file_name),
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<String>;
+
+    const DAY: u32 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        collect_lines(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1::run(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2::run(input).map(Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day2, part1, part2};
+    use crate::error::Error;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day2, EXAMPLE, P1), 8.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day2, EXAMPLE, P2), 2286.into());
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let lines = vec![EXAMPLE.lines().next().unwrap().to_string(), "Game 7 3 blue".to_string()];
+
+        assert!(matches!(part1::run(&lines), Err(Error::Parse { line: 2, column: 7, .. })));
+        assert!(matches!(part2::run(&lines), Err(Error::Parse { line: 2, column: 7, .. })));
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,4 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use crate::error::{Error, Result};
 use std::cmp;
 
 
@@ -12,28 +10,34 @@ struct Game {
 }
 
 
-pub fn run(file_name: &str) -> Result<u64, &'static str> {
+pub fn run(lines: &[String]) -> Result<u64> {
     let mut output = 0;
 
-    if let Ok(lines) = read_lines(file_name) {
-        for line in lines {
-            if let Ok(line) = line {
-                if let Some(game) = parse_game(&line) {
-                    if !is_possible(&game) {
-                        continue;
-                    }
-
-                    output += game.id;
-                }
-            }
+    for (i, line) in lines.iter().enumerate() {
+        let game = parse_game(i + 1, line)?;
+
+        if !is_possible(&game) {
+            continue;
         }
+
+        output += game.id;
     }
 
     Ok(output)
 }
 
 
-fn parse_game(line: &str) -> Option<Game> {
+fn parse_game(line_no: usize, line: &str) -> Result<Game> {
+    let Some(rest) = line.strip_prefix("Game ") else {
+        return Err(Error::parse(line_no, 1, line, "Expected a line starting with 'Game <id>:'"));
+    };
+
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+
+    if digits == 0 || !rest[digits..].starts_with(':') {
+        return Err(Error::parse(line_no, 6 + digits, line, "Expected a game id followed by ':'"));
+    }
+
     let mut last_number = 0;
     let mut game: Game = Game {
         id: 0,
@@ -67,14 +71,14 @@ fn parse_game(line: &str) -> Option<Game> {
         last_number = 0;
     }
 
-    Some(game)
+    Ok(game)
 }
 
 
 fn parse_digit(c: char) -> Option<u64> {
     let d = c as i32 - '0' as i32;
 
-    if !(d >= 0 && d <= 9)  {
+    if !(0..=9).contains(&d)  {
         return None;
     }
 
@@ -82,12 +86,5 @@ fn parse_digit(c: char) -> Option<u64> {
 }
 
 fn is_possible(game: &Game) -> bool {
-    return game.red <= 12 && game.green <= 13 && game.blue <= 14;
+    game.red <= 12 && game.green <= 13 && game.blue <= 14
 }
-
-
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
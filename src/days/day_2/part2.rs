@@ -1,6 +1,4 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use crate::error::{Error, Result};
 use std::cmp;
 
 
@@ -12,25 +10,31 @@ struct Game {
 }
 
 
-pub fn run(file_name: &str) -> Result<u64, &'static str> {
+pub fn run(lines: &[String]) -> Result<u64> {
     let mut output = 0;
 
-    if let Ok(lines) = read_lines(file_name) {
-        for line in lines {
-            if let Ok(line) = line {
-                if let Some(game) = parse_game(&line) {
-                    let power = game.red * game.blue * game.green;
-                    output += power;
-                }
-            }
-        }
+    for (i, line) in lines.iter().enumerate() {
+        let game = parse_game(i + 1, line)?;
+
+        let power = game.red * game.blue * game.green;
+        output += power;
     }
 
     Ok(output)
 }
 
 
-fn parse_game(line: &str) -> Option<Game> {
+fn parse_game(line_no: usize, line: &str) -> Result<Game> {
+    let Some(rest) = line.strip_prefix("Game ") else {
+        return Err(Error::parse(line_no, 1, line, "Expected a line starting with 'Game <id>:'"));
+    };
+
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+
+    if digits == 0 || !rest[digits..].starts_with(':') {
+        return Err(Error::parse(line_no, 6 + digits, line, "Expected a game id followed by ':'"));
+    }
+
     let mut last_number = 0;
     let mut game: Game = Game {
         id: 0,
@@ -64,22 +68,16 @@ fn parse_game(line: &str) -> Option<Game> {
         last_number = 0;
     }
 
-    Some(game)
+    Ok(game)
 }
 
 
 fn parse_digit(c: char) -> Option<u64> {
     let d = c as i32 - '0' as i32;
 
-    if !(d >= 0 && d <= 9)  {
+    if !(0..=9).contains(&d)  {
         return None;
     }
 
     Some(d as u64)
 }
-
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
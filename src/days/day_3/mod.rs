@@ -1,11 +1,68 @@
-use crate::days::Part;
+use crate::error::Result;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::grid::Grid;
+
+use std::io::BufRead;
 
 mod part1;
 mod part2;
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1::run(file_name),
-        Part::P2 => part2::run(file_name),
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Grid<char>;
+
+    const DAY: u32 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        Grid::parse(input, Some)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1::run(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2::run(input).map(Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day3;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day3, EXAMPLE, P1), 4361.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day3, EXAMPLE, P2), 467835.into());
+    }
+
+    #[test]
+    fn keeps_zeros_in_place() {
+        assert_eq!(solve_example(&Day3, "0.*\n...\n10*\n", P1), 10.into());
+        assert_eq!(solve_example(&Day3, "3*0\n", P1), 3.into());
+        assert_eq!(solve_example(&Day3, "7*0\n", P2), 0.into());
     }
-}
\ No newline at end of file
+}
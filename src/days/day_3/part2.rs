@@ -1,7 +1,6 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::log::{self, debug, Level};
 
 enum Entry {
     Symbol(char),
@@ -10,119 +9,27 @@ enum Entry {
 }
 
 
-pub fn run(file_name: &str) -> Result<u64, &'static str> {
-    let mut grid: Vec<Entry> = Vec::new();
+pub fn run(schematic: &Grid<char>) -> Result<u64> {
+    let mut cells: Vec<Entry> = Vec::new();
     let mut symbol_poss: Vec<(usize, usize)> = Vec::new();
-    let mut width = 0;
-    let mut height = 0;
     let mut id = 1;
 
-    if let Ok(lines) = read_lines(file_name) {
-        for line in lines {
-            if let Ok(line) = line {
-                parse_line(&mut grid, &mut symbol_poss, &line, height, &mut id);
-                height += 1 as usize;
-
-                if width == 0 {
-                    width = grid.len();
-                }
-            }
-        }
+    for (y, row) in schematic.rows().enumerate() {
+        parse_line(&mut cells, &mut symbol_poss, row, y, &mut id);
     }
 
+    let mut grid = Grid::new(schematic.width(), schematic.height(), cells);
     let mut sum = 0;
 
 
-    for (s_x, s_y) in symbol_poss {
-        let x = s_x as i32;
-        let y = s_y as i32;
+    'symbols: for (s_x, s_y) in symbol_poss {
         let mut sn = 0;
         let mut sn2 = 0;
         let mut product = 0;
+        let neighbors: Vec<(usize, usize)> = grid.neighbors8(s_x, s_y).collect();
     
-        // TL
-        if is_valid(x - 1, y -1, width, height) {
-            if let Some((n, i)) = mark_visited(x - 1, y - 1, width, &mut grid) {
-                if i == sn || i == sn2 {
-                    
-                } else if sn == 0 {
-                    sn = i;
-                    product = n;
-                } else if sn2 == 0 {
-                    sn2 = i;
-                    product *= n;
-                } else {
-                    continue;
-                }
-            }
-        }
-        // T
-        if is_valid(x, y -1, width, height) {
-            if let Some((n, i)) = mark_visited(x, y - 1, width, &mut grid) {
-                if i == sn || i == sn2 {
-                    
-                } else if sn == 0 {
-                    sn = i;
-                    product = n;
-                } else if sn2 == 0 {
-                    sn2 = i;
-                    product *= n;
-                } else {
-                    continue;
-                }
-            }
-        }
-        // TR
-        if is_valid(x + 1, y - 1, width, height) {
-            if let Some((n, i)) = mark_visited(x + 1, y - 1, width, &mut grid) {
-                if i == sn || i == sn2 {
-                    
-                } else if sn == 0 {
-                    sn = i;
-                    product = n;
-                } else if sn2 == 0 {
-                    sn2 = i;
-                    product *= n;
-                } else {
-                    continue;
-                }
-            }
-        }
-        // L
-        if is_valid(x - 1, y, width, height) {
-            if let Some((n, i)) = mark_visited(x - 1, y, width, &mut grid) {
-                if i == sn || i == sn2 {
-                    
-                } else if sn == 0 {
-                    sn = i;
-                    product = n;
-                } else if sn2 == 0 {
-                    sn2 = i;
-                    product *= n;
-                } else {
-                    continue;
-                }
-            }
-        }
-        // R
-        if is_valid(x + 1, y, width, height) {
-            if let Some((n, i)) = mark_visited(x + 1, y, width, &mut grid) {
-                if i == sn || i == sn2 {
-                    
-                } else if sn == 0 {
-                    sn = i;
-                    product = n;
-                } else if sn2 == 0 {
-                    sn2 = i;
-                    product *= n;
-                } else {
-                    continue;
-                }
-            }
-        }
-        // BL
-        if is_valid(x - 1, y + 1, width, height) {
-            if let Some((n, i)) = mark_visited(x - 1, y + 1, width, &mut grid) {
+        for (x, y) in neighbors {
+            if let Some((n, i)) = mark_visited(x, y, &mut grid) {
                 if i == sn || i == sn2 {
                     
                 } else if sn == 0 {
@@ -132,39 +39,7 @@ pub fn run(file_name: &str) -> Result<u64, &'static str> {
                     sn2 = i;
                     product *= n;
                 } else {
-                    continue;
-                }
-            }
-        }
-        // B 
-        if is_valid(x, y + 1, width, height) {
-            if let Some((n, i)) = mark_visited(x, y + 1, width, &mut grid) {
-                if i == sn || i == sn2 {
-                    
-                } else if sn == 0 {
-                    sn = i;
-                    product = n;
-                } else if sn2 == 0 {
-                    sn2 = i;
-                    product *= n;
-                } else {
-                    continue;
-                }
-            }
-        }
-        // BR 
-        if is_valid(x + 1, y + 1, width, height) {
-            if let Some((n, i)) = mark_visited(x + 1, y + 1, width, &mut grid) {
-                if i == sn || i == sn2 {
-                    
-                } else if sn == 0 {
-                    sn = i;
-                    product = n;
-                } else if sn2 == 0 {
-                    sn2 = i;
-                    product *= n;
-                } else {
-                    continue;
+                    continue 'symbols;
                 }
             }
         }
@@ -174,32 +49,26 @@ pub fn run(file_name: &str) -> Result<u64, &'static str> {
         }
     }
 
-    // print_grid(&grid, width, height);
+    print_grid(&grid);
 
     
     Ok(sum)
 }
 
-fn mark_visited(x: i32, y: i32, width: usize, grid: &mut Vec<Entry>) -> Option<(u64, u64)> {
-    let pos = (y * width as i32) + x;
-    if let Entry::Number(n, i, _) = grid[pos as usize] {
-        grid[pos as usize] = Entry::Number(n, i, true);
+fn mark_visited(x: usize, y: usize, grid: &mut Grid<Entry>) -> Option<(u64, u64)> {
+    if let Entry::Number(n, i, _) = grid[(x, y)] {
+        grid[(x, y)] = Entry::Number(n, i, true);
         return Some((n, i));
     }
-    return None;
+    None
 }
 
-fn is_valid(x: i32, y: i32, width: usize, height: usize) -> bool {
-    return x >= 0 && y >= 0 && x < width as i32 && y < height as i32;
-}
-
-
-fn parse_line(grid: &mut Vec<Entry>, symbol_poss: &mut Vec<(usize, usize)>,line: &str, height: usize, id: &mut u64) {
+fn parse_line(grid: &mut Vec<Entry>, symbol_poss: &mut Vec<(usize, usize)>,line: &[char], height: usize, id: &mut u64) {
     let mut current_number = 0;
     let mut current_number_size = 0;
 
-    for (i, c) in line.chars().enumerate() {
-        if (c < '0' || c > '9') && current_number != 0 {
+    for (i, &c) in line.iter().enumerate() {
+        if !c.is_ascii_digit() && current_number_size != 0 {
             for _i in 0..current_number_size {
                 grid.push(Entry::Number(current_number, *id, false));
             }
@@ -208,7 +77,7 @@ fn parse_line(grid: &mut Vec<Entry>, symbol_poss: &mut Vec<(usize, usize)>,line:
             *id += 1;
         } 
         
-        if c >= '0' && c <= '9' {
+        if c.is_ascii_digit() {
             current_number_size += 1;
             current_number = current_number * 10 + (c as u64 - '0' as u64);
         } else if c == '.' {
@@ -221,7 +90,7 @@ fn parse_line(grid: &mut Vec<Entry>, symbol_poss: &mut Vec<(usize, usize)>,line:
         }
     }   
 
-    if current_number != 0 {
+    if current_number_size != 0 {
         for _ in 0..current_number_size {
             grid.push(Entry::Number(current_number, *id, false));
         }
@@ -230,39 +99,36 @@ fn parse_line(grid: &mut Vec<Entry>, symbol_poss: &mut Vec<(usize, usize)>,line:
 }
 
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
+/* The schematic with numbers next to a symbol in green and the rest in red */
+fn print_grid(grid: &Grid<Entry>) {
+    if !log::enabled(Level::Debug) {
+        return;
+    }
 
-fn _print_grid(grid: &Vec<Entry>, width: usize, height: usize) {
-    for y in 0..height {
+    for row in grid.rows() {
+        let mut line = String::new();
         let mut curr_number_visited = false;
-        for x in 0..width {
-            let pos = (y * width) + x;
-            match grid[pos as usize] {
-                Entry::Empty => print!("."),
-                Entry::Symbol(c) => print!("{}", c),
+        for (x, entry) in row.iter().enumerate() {
+            match entry {
+                Entry::Empty => line.push('.'),
+                Entry::Symbol(c) => line.push(*c),
                 Entry::Number(n, _, v) => {
-                    curr_number_visited = curr_number_visited || v;
+                    curr_number_visited = curr_number_visited || *v;
 
-                    if (x + 1) < width {
-                        if let Entry::Number(_, _, v2) = grid[pos + 1] {
-                            curr_number_visited = curr_number_visited || v2;
-                            continue;
-                        } 
+                    if let Some(Entry::Number(_, _, v2)) = row.get(x + 1) {
+                        curr_number_visited = curr_number_visited || *v2;
+                        continue;
                     }
 
                     if curr_number_visited {
-                        print!("\x1b[92m{}\x1b[0m", n);
+                        line += &format!("\x1b[92m{}\x1b[0m", n);
                     } else {
-                        print!("\x1b[91m{}\x1b[0m", n)
+                        line += &format!("\x1b[91m{}\x1b[0m", n);
                     }
                     curr_number_visited = false;
                 },
             }
         }
-        println!();
+        debug!("{}", line);
     }
 }
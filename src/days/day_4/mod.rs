@@ -1,11 +1,66 @@
-use crate::days::Part;
+use crate::days::collect_lines;
+use crate::error::Result;
+use crate::answer::Answer;
+use crate::solution::Solution;
+
+use std::io::BufRead;
 
 mod part1;
 mod part2;
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1::run(file_name),
-        Part::P2 => part2::run(file_name),
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<String>;
+
+    const DAY: u32 = 4;
+    const TITLE: &'static str = "Scratchcards";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        collect_lines(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1::run(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2::run(input).map(Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day4, part1, part2};
+    use crate::error::Error;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day4, EXAMPLE, P1), 13.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day4, EXAMPLE, P2), 30.into());
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let lines = vec![EXAMPLE.lines().next().unwrap().to_string(), "Card 1: 41 48 83".to_string()];
+
+        assert!(matches!(part1::run(&lines), Err(Error::Parse { line: 2, column: 17, .. })));
+        assert!(matches!(part2::run(&lines), Err(Error::Parse { line: 2, column: 17, .. })));
     }
-}
\ No newline at end of file
+}
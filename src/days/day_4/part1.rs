@@ -1,42 +1,47 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use crate::error::{Error, Result};
 
-
-pub fn run(file_name: &str) -> Result<u64, &'static str> {
+pub fn run(lines: &[String]) -> Result<u64> {
     let mut output = 0;
 
-    if let Ok(lines) = read_lines(file_name) {
-        for line in lines {
-            if let Ok(line) = line {
-                let mut c_ns = Vec::new();
-                let mut w_ns = Vec::new();
-                let mut card_score = 0;
-
-                parse_line(&mut c_ns, &mut w_ns, &line);
-
-                for n in &c_ns {
-                    if w_ns.contains(n) {
-                        if card_score == 0 {
-                            card_score = 1;
-                        } else {
-                            card_score *= 2;
-                        }
-                        continue;
-                    }
+    for (i, line) in lines.iter().enumerate() {
+        let mut c_ns = Vec::new();
+        let mut w_ns = Vec::new();
+        let mut card_score = 0;
+
+        parse_line(&mut c_ns, &mut w_ns, i + 1, line)?;
+
+        for n in &c_ns {
+            if w_ns.contains(n) {
+                if card_score == 0 {
+                    card_score = 1;
+                } else {
+                    card_score *= 2;
                 }
-                
-                output += card_score;
-                // println!("{} {} {}", c_ns.len(), w_ns.len(), output);
+                continue;
             }
         }
+        
+        output += card_score;
+        // println!("{} {} {}", c_ns.len(), w_ns.len(), output);
     }
 
 
     Ok(output)
 }
 
-fn parse_line(card_numbers: &mut Vec<u64>, winning_numbers: &mut Vec<u64>, line: &str) {
+fn parse_line(card_numbers: &mut Vec<u64>, winning_numbers: &mut Vec<u64>, line_no: usize, line: &str) -> Result<()> {
+    if !line.starts_with("Card ") {
+        return Err(Error::parse(line_no, 1, line, "Expected a line starting with 'Card <id>:'"));
+    }
+
+    let Some(colon) = line.find(':') else {
+        return Err(Error::parse(line_no, line.len() + 1, line, "Missing ':' after the card id"));
+    };
+
+    if !line[colon..].contains('|') {
+        return Err(Error::parse(line_no, line.len() + 1, line, "Missing '|' between card and winning numbers"));
+    }
+
     let mut start = false;
     let mut parsed_cn = false;
 
@@ -50,7 +55,7 @@ fn parse_line(card_numbers: &mut Vec<u64>, winning_numbers: &mut Vec<u64>, line:
             continue;
         }
 
-        if c >= '0' && c <= '9' {
+        if c.is_ascii_digit() {
             current_number = current_number * 10 + (c as u64 - '0' as u64);
             continue;
         }
@@ -72,11 +77,6 @@ fn parse_line(card_numbers: &mut Vec<u64>, winning_numbers: &mut Vec<u64>, line:
         current_number = 0;
     }
     winning_numbers.push(current_number);
-}
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    Ok(())
 }
-
@@ -1,25 +1,18 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use crate::error::{Error, Result};
 
-
-pub fn run(file_name: &str) -> Result<u64, &'static str> {
+pub fn run(lines: &[String]) -> Result<u64> {
     let mut output = 0;
     let mut cards = Vec::<(Vec<u64>, Vec<u64>)>::new();
     let mut card_amounts = Vec::new();
 
-    if let Ok(lines) = read_lines(file_name) {
-        for line in lines {
-            if let Ok(line) = line {
-                let mut c_ns = Vec::new();
-                let mut w_ns = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let mut c_ns = Vec::new();
+        let mut w_ns = Vec::new();
 
-                parse_line(&mut c_ns, &mut w_ns, &line);
+        parse_line(&mut c_ns, &mut w_ns, i + 1, line)?;
 
-                cards.push((c_ns, w_ns));
-                card_amounts.push(1);
-            }
-        }
+        cards.push((c_ns, w_ns));
+        card_amounts.push(1);
     }
 
     for (i, (c_ns, w_ns)) in cards.iter().enumerate() {
@@ -51,7 +44,19 @@ pub fn run(file_name: &str) -> Result<u64, &'static str> {
     Ok(output)
 }
 
-fn parse_line(card_numbers: &mut Vec<u64>, winning_numbers: &mut Vec<u64>, line: &str) {
+fn parse_line(card_numbers: &mut Vec<u64>, winning_numbers: &mut Vec<u64>, line_no: usize, line: &str) -> Result<()> {
+    if !line.starts_with("Card ") {
+        return Err(Error::parse(line_no, 1, line, "Expected a line starting with 'Card <id>:'"));
+    }
+
+    let Some(colon) = line.find(':') else {
+        return Err(Error::parse(line_no, line.len() + 1, line, "Missing ':' after the card id"));
+    };
+
+    if !line[colon..].contains('|') {
+        return Err(Error::parse(line_no, line.len() + 1, line, "Missing '|' between card and winning numbers"));
+    }
+
     let mut start = false;
     let mut parsed_cn = false;
 
@@ -65,7 +70,7 @@ fn parse_line(card_numbers: &mut Vec<u64>, winning_numbers: &mut Vec<u64>, line:
             continue;
         }
 
-        if c >= '0' && c <= '9' {
+        if c.is_ascii_digit() {
             current_number = current_number * 10 + (c as u64 - '0' as u64);
             continue;
         }
@@ -87,11 +92,6 @@ fn parse_line(card_numbers: &mut Vec<u64>, winning_numbers: &mut Vec<u64>, line:
         current_number = 0;
     }
     winning_numbers.push(current_number);
-}
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    Ok(())
 }
-
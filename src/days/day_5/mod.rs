@@ -1,20 +1,36 @@
-use crate::days::Part;
-use crate::days::{read_lines, parse_num};
-
-use std::fs::File;
-use std::io::{self};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::days::{parse_num, NumberedLines};
+use crate::interval::{Interval, IntervalSet};
+use crate::search;
+use crate::log::{info, debug};
+
+use std::io::BufRead;
 use std::cmp::Ordering;
-use std::cmp;
 
-struct Input {
+pub struct Input {
     seeds: Vec<u64>,
-    seed_to_soil: Vec<MapElement>,
-    soil_to_fertilizer: Vec<MapElement>,
-    fertilizer_to_water: Vec<MapElement>,
-    water_to_light: Vec<MapElement>,
-    light_to_temerature: Vec<MapElement>,
-    temerature_to_humidity: Vec<MapElement>,
-    humidity_to_location: Vec<MapElement>
+    maps: Vec<Map>
+}
+
+/* One "source-to-destination map:" block of the almanac */
+struct Map {
+    source: String,
+    destination: String,
+    elements: Vec<MapElement>
+}
+
+/* A map layer as sorted segments, each moving its numbers by delta */
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Mapping {
+    segments: Vec<Segment>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    source: Interval,
+    delta: i64
 }
 
 #[derive(Eq)]
@@ -25,89 +41,197 @@ struct MapElement {
 }
 
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Input;
+
+    const DAY: u32 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_input(input)
     }
-}
 
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
 
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
+}
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let input = parse_input(file_name)?;
 
-    // print_input(&input);
-    let mut min = std::u64::MAX;
-    for pair in input.seeds.chunks(2) {
-        let s_min = pair[0];
-        let s_max = pair[0] + pair[1];
-        let mut s = s_min;
 
-        println!("{}", s_min);
+fn part1(input: &Input) -> Result<u64> {
+    let mapping = seed_to_location(input)?;
 
-        loop {
-            if s >= s_max {
-                break;
-            }
+    let locations = input.seeds.iter()
+        .filter_map(|&seed| mapping.forward_point(seed as i64));
 
-            let (n, c) = get_soil_number(&input, s);
-            min = cmp::min(min, n);
+    match locations.min() {
+        Some(location) => Ok(location as u64),
+        None => Err(Error::unsolvable("No seeds to plant")),
+    }
+}
 
-            s += cmp::max(1, c);
-        }
+
+fn part2(input: &Input) -> Result<u64> {
+    /* Seeds come in pairs of range start and length */
+    if !input.seeds.len().is_multiple_of(2) {
+        return Err(Error::invalid_state("Seed ranges need both a start and a length"));
     }
 
-    Ok(min as u64)
+    let seeds: IntervalSet = input.seeds.chunks(2)
+        .map(|pair| Interval::from_len(pair[0] as i64, pair[1] as i64))
+        .collect();
+    let mapping = seed_to_location(input)?;
+
+    let Some(location) = mapping.forward(&seeds).min() else {
+        return Err(Error::unsolvable("No seeds to plant"));
+    };
+
+    info!("Seeds planted at location {}: {}", location, mapping.backward_point(location).intersection(&seeds));
+
+    Ok(location as u64)
 }
 
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let input = parse_input(file_name)?;
+fn seed_to_location(input: &Input) -> Result<Mapping> {
+    let Some(mapping) = input.mapping("seed", "location") else {
+        return Err(Error::unsolvable("No maps lead from seed to location"));
+    };
 
-    // print_input(&input);
-    let mut min = std::u64::MAX;
-    for s in &input.seeds {
-        let (n, _) = get_soil_number(&input, *s);
-        // println!("s{} n{}", *s, n);
-        if n < min {
-            min = n;
-        }
-    }
+    debug!("{:?}", mapping.segments);
 
-    Ok(min as u64)
+    Ok(mapping)
 }
 
 
-fn get_soil_number(input: &Input, seed: u64) -> (u64, u64) {
-    let mut min_change = std::u64::MAX;
+impl Input {
+    /* The maps leading from one category to another, fewest first */
+    fn chain(&self, from: &str, to: &str) -> Option<Vec<&Map>> {
+        let neighbors = |category: &&str| {
+            self.maps.iter()
+                .filter(|map| map.source == *category)
+                .map(|map| map.destination.as_str())
+                .collect::<Vec<&str>>()
+        };
+
+        let (_, categories) = search::bfs(from, neighbors, |category| *category == to)?;
+
+        categories.windows(2)
+            .map(|pair| self.maps.iter().find(|map| map.source == pair[0] && map.destination == pair[1]))
+            .collect()
+    }
 
-    let soil = get_mapping(seed, &input.seed_to_soil, &mut min_change);
-    let fert = get_mapping(soil, &input.soil_to_fertilizer, &mut min_change);
-    let water = get_mapping(fert, &input.fertilizer_to_water, &mut min_change);
-    let light = get_mapping(water, &input.water_to_light, &mut min_change);
-    let temp = get_mapping(light, &input.light_to_temerature, &mut min_change);
-    let hum = get_mapping(temp, &input.temerature_to_humidity, &mut min_change);
-    let loc = get_mapping(hum, &input.humidity_to_location, &mut min_change);
+    /* Every map from one category to another composed into one */
+    fn mapping(&self, from: &str, to: &str) -> Option<Mapping> {
+        let chain = self.chain(from, to)?;
 
-    return (loc, min_change);
+        Some(chain.iter().fold(Mapping::new(&[]), |mapping, map| {
+            mapping.compose(&Mapping::new(&map.elements))
+        }))
+    }
 }
 
-fn get_mapping(n: u64, map: &Vec<MapElement>, min_change: &mut u64) -> u64 {
-    for el in map {
-        if n < el.src_range_start {
-            *min_change = cmp::min(*min_change, el.src_range_start - n);
-            return n;
+
+impl Mapping {
+    /* One map layer, with numbers outside every entry mapping to themselves */
+    fn new(elements: &[MapElement]) -> Mapping {
+        let sources: IntervalSet = elements.iter().map(MapElement::source).collect();
+        let unmapped = IntervalSet::from(Interval::new(0, i64::MAX)).difference(&sources);
+
+        let mapped = elements.iter().map(|el| Segment { source: el.source(), delta: el.delta() });
+        let unmapped = unmapped.iter().map(|&source| Segment { source, delta: 0 });
+
+        Mapping::from_segments(mapped.chain(unmapped))
+    }
+
+    /* Sorts the segments and joins neighbours that move by the same delta */
+    fn from_segments<I: IntoIterator<Item = Segment>>(segments: I) -> Mapping {
+        let mut sorted: Vec<Segment> = segments.into_iter().filter(|s| !s.source.is_empty()).collect();
+        let mut segments: Vec<Segment> = Vec::with_capacity(sorted.len());
+
+        sorted.sort_by_key(|s| (s.source, s.delta));
+
+        for segment in sorted {
+            match segments.last_mut() {
+                Some(last) if last.delta == segment.delta && segment.source.start <= last.source.end => {
+                    last.source.end = last.source.end.max(segment.source.end);
+                },
+                _ => segments.push(segment),
+            }
         }
 
-        if n < el.src_range_start + el.length {
-            let change = (el.src_range_start + el.length) - n;
-            *min_change = cmp::min(*min_change, change);
-            return el.des_range_start + (n - el.src_range_start)
+        Mapping { segments }
+    }
+
+    /* Applies self and then next */
+    fn compose(&self, next: &Mapping) -> Mapping {
+        let mut segments = Vec::new();
+
+        for segment in &self.segments {
+            let image = segment.source.shift(segment.delta);
+
+            for after in &next.segments {
+                let Some(overlap) = image.intersection(&after.source) else {
+                    continue;
+                };
+
+                segments.push(Segment {
+                    source: overlap.shift(-segment.delta),
+                    delta: segment.delta + after.delta
+                });
+            }
         }
+
+        Mapping::from_segments(segments)
+    }
+
+    /*
+     * Maps numbers back to where they came from. Several numbers can map to
+     * the same one, so the segments of an inverse may overlap.
+     */
+    fn invert(&self) -> Mapping {
+        Mapping::from_segments(self.segments.iter().map(|segment| Segment {
+            source: segment.source.shift(segment.delta),
+            delta: -segment.delta
+        }))
+    }
+
+    fn forward(&self, ranges: &IntervalSet) -> IntervalSet {
+        self.segments.iter().fold(IntervalSet::default(), |image, segment| {
+            image.union(&ranges.intersection(&segment.source.into()).shift(segment.delta))
+        })
+    }
+
+    fn forward_point(&self, n: i64) -> Option<i64> {
+        self.segments.iter()
+            .find(|segment| segment.source.contains(n))
+            .map(|segment| n + segment.delta)
+    }
+
+    /* Every number that maps into ranges */
+    fn backward(&self, ranges: &IntervalSet) -> IntervalSet {
+        self.invert().forward(ranges)
+    }
+
+    fn backward_point(&self, n: i64) -> IntervalSet {
+        self.backward(&Interval::from_len(n, 1).into())
+    }
+}
+
+
+impl MapElement {
+    fn source(&self) -> Interval {
+        Interval::from_len(self.src_range_start as i64, self.length as i64)
     }
 
-    return n;
+    fn delta(&self) -> i64 {
+        self.des_range_start as i64 - self.src_range_start as i64
+    }
 }
 
 
@@ -129,83 +253,78 @@ impl PartialEq for MapElement {
     }
 }
 
-fn parse_input(file_name: &str) -> Result<Input, &'static str> {
-    let Ok(mut lines) = read_lines(file_name) else {
-        return Err("Failed to read file");
-    };
+fn parse_input<B: BufRead>(input: B) -> Result<Input> {
+    let mut lines = input.lines().enumerate();
 
-    let Some(seeds) = parse_seeds(&mut lines) else {
-        return Err("Failed to parse seeds");
-    };
+    let seeds = parse_seeds(&mut lines)?;
+    let mut maps = Vec::new();
 
-    let Some(seed_to_soil) = parse_map(&mut lines) else {
-        return Err("Failed to parse soil to soil");
-    };
+    while let Some(map) = parse_map(&mut lines)? {
+        maps.push(map);
+    }
 
-    let Some(soil_to_fertilizer) = parse_map(&mut lines) else {
-        return Err("Failed to parse soil_to_fertilizer");
-    };
+    if maps.is_empty() {
+        return Err(Error::parse(2, 1, "", "Expected at least one map after the seeds"));
+    }
+ 
+    Ok(Input {
+        seeds,
+        maps
+    })
+}
 
-    let Some(fertilizer_to_water) = parse_map(&mut lines) else {
-        return Err("Failed to parse fertilizer_to_water");
-    };
 
-    let Some(water_to_light) = parse_map(&mut lines) else {
-        return Err("Failed to parse water_to_light");
-    };
+/* Reads the next map block, or None once the input runs out */
+fn parse_map<B: BufRead>(lines: &mut NumberedLines<B>) -> Result<Option<Map>> {
+    let (i, header) = loop {
+        let Some((i, l)) = lines.next() else {
+            return Ok(None);
+        };
+        let line = l?;
 
-    let Some(light_to_temerature) = parse_map(&mut lines) else {
-        return Err("Failed to parse light_to_temerature");
+        if !line.trim().is_empty() {
+            break (i, line);
+        }
     };
 
-    let Some(temerature_to_humidity) = parse_map(&mut lines) else {
-        return Err("Failed to parse temerature_to_humidity");
+    let name = header.trim().strip_suffix(" map:");
+    let Some((source, destination)) = name.and_then(|name| name.split_once("-to-")) else {
+        return Err(Error::parse(i + 1, 1, &header, "Expected a map header like 'seed-to-soil map:'"));
     };
 
-    let Some(humidity_to_location) = parse_map(&mut lines) else {
-        return Err("Failed to parse humidity_to_location");
-    };
- 
-    Ok(Input {
-        seeds,
-        seed_to_soil,
-        soil_to_fertilizer,
-        fertilizer_to_water,
-        water_to_light,
-        light_to_temerature,
-        temerature_to_humidity,
-        humidity_to_location,
-    })
-}
+    let mut elements = Vec::new();
+    let mut line_no = i + 1;
 
+    for (i, l) in lines.by_ref() {
+        let line = l?;
+        line_no = i + 1;
 
-fn parse_map(lines: &mut io::Lines<io::BufReader<File>>) -> Option<Vec<MapElement>> {
-    let mut elements = Vec::new();
+        if line.trim().is_empty() {
+            break; /* break in map */
+        }
 
-    while let Some(l) = lines.next() {
-        let Ok(line) = l else {
-            break;
+        let Some(el) = parse_map_element(&line) else {
+            return Err(Error::parse(
+                line_no, 1, &line, format!("Expected 3 numbers in {source}-to-{destination} map entry")
+            ));
         };
 
-        match parse_map_element(&line) {
-            Some(el) => elements.push(el),
-            None => {
-                if elements.len() == 0 {
-                    continue; /* Skipping text at start */
-                } else {
-                    break; /* break in map */
-                }
-            }
-        }
+        elements.push(el);
+    }
+
+    if elements.is_empty() {
+        return Err(Error::parse(
+            line_no + 1, 1, "", format!("Missing entries for {source}-to-{destination} map")
+        ));
     }
 
     elements.sort();
 
-    if elements.len() == 0 {
-        None 
-    } else {
-        Some(elements)
-    }
+    Ok(Some(Map {
+        source: source.to_string(),
+        destination: destination.to_string(),
+        elements
+    }))
 }
 
 
@@ -214,15 +333,11 @@ fn parse_map_element(line: &str) -> Option<MapElement> {
     let chars_vec = line.chars().collect::<Vec<char>>();
     let mut chars = chars_vec.iter().peekable();
 
-    loop {
-        let Some(n) = parse_num(&mut chars, true) else {
-            break;
-        };
-
+    while let Some(n) = parse_num(&mut chars, true) {
         nums.push(n as u64);
     }
 
-    return if nums.len() != 3 {
+    if nums.len() != 3 {
         None
     } else {
         Some(MapElement{
@@ -230,59 +345,135 @@ fn parse_map_element(line: &str) -> Option<MapElement> {
             src_range_start: nums[1],
             length: nums[2]
         })
-    };
+    }
 }
 
 
-fn parse_seeds(lines: &mut io::Lines<io::BufReader<File>>) -> Option<Vec<u64>>{
-    let Ok(line) = lines.next()? else {
-        return None;
+fn parse_seeds<B: BufRead>(lines: &mut NumberedLines<B>) -> Result<Vec<u64>> {
+    let Some((i, l)) = lines.next() else {
+        return Err(Error::parse(1, 1, "", "Missing seeds line"));
     };
+    let line = l?;
     let chars_vec = line.chars().collect::<Vec<char>>();
     let mut chars = chars_vec.iter().peekable();
     let mut seeds = Vec::new();
 
-    loop {
-        let Some(n) = parse_num(&mut chars, true) else {
-            break;
-        };
-
+    while let Some(n) = parse_num(&mut chars, true) {
         seeds.push(n as u64);
     }
 
-    Some(seeds)
+    if seeds.is_empty() {
+        return Err(Error::parse(i + 1, 1, &line, "Expected a list of seeds"));
+    }
+
+    Ok(seeds)
 }
 
 fn _print_input(input: &Input) {
-    println!("Seeds:");
-    print!("    ");
-
-    for s in &input.seeds {
-        print!("{} ", s);
-    }
-
-    println!("");
-
-    println!("seed_to_soil: ");
-    _print_mapping(&input.seed_to_soil);
-    println!("soil_to_fertilizer: ");
-    _print_mapping(&input.soil_to_fertilizer);
-    println!("fertilizer_to_water: ");
-    _print_mapping(&input.fertilizer_to_water);
-    println!("water_to_light: ");
-    _print_mapping(&input.water_to_light);
-    println!("light_to_temerature: ");
-    _print_mapping(&input.light_to_temerature);
-    println!("temerature_to_humidity: ");
-    _print_mapping(&input.temerature_to_humidity);
-    println!("humidity_to_location: ");
-    _print_mapping(&input.humidity_to_location);
+    debug!("Seeds:");
+    debug!("    {}", input.seeds.iter().map(|s| format!("{} ", s)).collect::<String>());
+
+    for map in &input.maps {
+        debug!("{}_to_{}: ", map.source, map.destination);
+        _print_mapping(&map.elements);
+    }
 }
 
 fn _print_mapping(input: &Vec<MapElement>) {
     for el in input {
-        println!("    s{} d{} l{}", 
+        debug!("    s{} d{} l{}", 
             el.src_range_start, el.des_range_start, el.length
         );
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day5, Mapping, parse_input};
+    use crate::days::solve_example;
+    use crate::interval::{Interval, IntervalSet};
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day5, EXAMPLE, P1), 35.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day5, EXAMPLE, P2), 46.into());
+    }
+
+    #[test]
+    fn maps_between_any_categories() {
+        let input = parse_input(EXAMPLE.as_bytes()).unwrap();
+        let soil_to_light = input.mapping("soil", "light").unwrap();
+
+        assert_eq!(soil_to_light.forward_point(81), Some(74));
+        assert!(input.mapping("light", "soil").is_none());
+
+        let short = parse_input("seeds: 1 2\n\nseed-to-rock map:\n10 0 5\n".as_bytes()).unwrap();
+        let seeds = IntervalSet::from(Interval::from_len(1, 2));
+        assert_eq!(short.mapping("seed", "rock").unwrap().forward(&seeds), Interval::from_len(11, 2).into());
+    }
+
+    #[test]
+    fn composes_and_inverts_layers() {
+        let input = parse_input(EXAMPLE.as_bytes()).unwrap();
+        let mapping = input.mapping("seed", "location").unwrap();
+
+        /* The chain composed into one layer agrees with going step by step */
+        for seed in 0..120 {
+            let stepped = input.maps.iter().fold(seed, |n, map| {
+                Mapping::new(&map.elements).forward_point(n).unwrap()
+            });
+            assert_eq!(mapping.forward_point(seed), Some(stepped));
+        }
+
+        /* Seeds 79, 14, 55 and 13 end up at 82, 43, 86 and 35 */
+        for (seed, location) in [(79, 82), (14, 43), (55, 86), (13, 35)] {
+            assert!(mapping.backward_point(location).iter().any(|seeds| seeds.contains(seed)));
+        }
+
+        let locations = IntervalSet::from(Interval::new(46, 47));
+        let seeds = mapping.backward(&locations);
+        assert_eq!(mapping.forward(&seeds), locations);
+        assert!(seeds.iter().any(|seeds| seeds.contains(82)));
+    }
+}
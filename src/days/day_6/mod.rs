@@ -1,172 +1,171 @@
-use crate::days::Part;
-use crate::days::{read_lines, parse_num};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::days::NumberedLines;
+use crate::log::debug;
 
-use std::fs::File;
-use std::io::{self};
+use std::io::BufRead;
 
+use num::{BigInt, Integer, One, Signed, Zero};
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-  match part {
-      Part::P1 => part1(file_name),
-      Part::P2 => part2(file_name),
-  }
-}
 
+pub struct Day6;
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-  let races = parse_races(file_name)?;
-  let mut product = 1;
+impl Solution for Day6 {
+  type Input = Vec<(BigInt, BigInt)>;
 
-  for (time, dist) in races {
-    // println!("race -- {} {}", time, dist);
-    for start_speed in 1..time {
-      let distance = start_speed * (time - start_speed);
+  const DAY: u32 = 6;
+  const TITLE: &'static str = "Wait For It";
 
-      // println!("  - {} {}", start_speed, distance);
+  fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+      parse_races(input)
+  }
 
-      if distance > dist {
-        let amount = (time - 1) - (start_speed - 1) * 2;
-        // println!("  - done {}", amount);
-        product *= amount;
-        break;
-      }
-    }
+  fn part1(&self, input: &Self::Input) -> Result<Answer> {
+      part1(input).map(Answer::from)
   }
 
-  Ok(product as u64)
+  fn part2(&self, input: &Self::Input) -> Result<Answer> {
+      part2(input).map(Answer::from)
+  }
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-  let races = parse_races(file_name)?;
-  let races = combine_races(races);
-  let mut product = 1;
-  // 47986609
-
-  for (time, dist) in &races {
-    // println!("race -- {} {}", time, dist);
-    let mut start_speed = 1;
-    let mut left = 1;
-    let mut right = *time - 1;
-
-    while left <= right {
-      start_speed = (left + right) / 2;
-      let distance_less = (start_speed - 1) * (*time - start_speed);
-      let distance_more = (start_speed + 1) * (*time - start_speed);
-  
-        // println!("  - {} {}", start_speed, distance);
-
-        if distance_less > *dist {
-          right = start_speed - 1;
-          continue;
-        }
-
-        if distance_more < *dist {
-          left = start_speed + 1;
-          continue;
-        }
-
-        // println!("  - done");
-        break;
-    }
+fn part1(races: &[(BigInt, BigInt)]) -> Result<BigInt> {
+  let mut product = BigInt::one();
 
-    for (time, dist) in &races {
-      // println!("race -- {} {}", time, dist);
-      for start_speed in start_speed..*time {
-        let distance = start_speed * (*time - start_speed);
-  
-        // println!("  - {} {}", start_speed, distance);
-  
-        if distance > *dist {
-          let amount = (*time - 1) - (start_speed - 1) * 2;
-          // println!("  - done {}", amount);
-          product *= amount;
-          break;
-        }
-      }
-    }
+  for (time, dist) in races {
+    let wins = winning_hold_times(time, dist);
+    debug!("race {} {} - {} ways to win", time, dist, wins);
+
+    product *= wins;
   }
 
-  Ok(product as u64)
+  Ok(product)
 }
 
 
-fn combine_races(races: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
-  let mut output = Vec::new();
-  let mut t = 0;
-  let mut d = 0;
+fn part2(races: &[(BigInt, BigInt)]) -> Result<BigInt> {
+  let (time, dist) = combine_races(races)?;
+
+  Ok(winning_hold_times(&time, &dist))
+}
+
 
-  let base = 10 as u64;
+/*
+ * Holding for h of time t goes h * (t - h), which beats d when
+ * (2h - t)^2 < t^2 - 4d. So 2h - t can be any number of the same parity
+ * as t within m of zero, m being the largest root with m^2 below the
+ * discriminant.
+ */
+fn winning_hold_times(time: &BigInt, dist: &BigInt) -> BigInt {
+  let discriminant: BigInt = time * time - dist * 4;
 
-  for (time, dist) in  races {
-    t = (t * base.pow(length(time, 10) as u32)) + time;
-    d = (d * base.pow(length(dist, 10) as u32)) + dist;
+  if !discriminant.is_positive() {
+    return BigInt::zero();
   }
 
-  output.push((t, d));
+  let m = (discriminant - BigInt::one()).sqrt();
 
-  output
+  if (&m - time).is_even() {
+    m + 1
+  } else {
+    m
+  }
 }
 
 
+/* The races were really one race with the spaces between digits removed */
+fn combine_races(races: &[(BigInt, BigInt)]) -> Result<(BigInt, BigInt)> {
+  let time: String = races.iter().map(|(time, _)| time.to_string()).collect();
+  let dist: String = races.iter().map(|(_, dist)| dist.to_string()).collect();
 
-fn parse_races(file_name: &str) -> Result<Vec<(u64, u64)>, &'static str> {
-  let Ok(mut lines) = read_lines(file_name) else {
-      return Err("Failed to read file");
-  };
+  match (time.parse(), dist.parse()) {
+    (Ok(time), Ok(dist)) => Ok((time, dist)),
+    _ => Err(Error::invalid_state("No races to combine")),
+  }
+}
 
-  let Some(times) = parse_line(&mut lines) else {
-    return Err("Failed to parse times");
-  };
 
-  let Some(distances) = parse_line(&mut lines) else {
-    return Err("Failed to parse distances");
-  };
 
-  if times.len() != distances.len() {
-    return Err("mistmatch");
-  }
+fn parse_races<B: BufRead>(input: B) -> Result<Vec<(BigInt, BigInt)>> {
+  let mut lines = input.lines().enumerate();
 
-  let mut output = Vec::new();
+  let (times, _) = parse_line(&mut lines, 1, "times")?;
+  let (distances, (line_no, line)) = parse_line(&mut lines, 2, "distances")?;
 
-  for i in 0..(times.len()) {
-    output.push((times[i], distances[i]));
+  if times.len() != distances.len() {
+    return Err(Error::parse(
+      line_no, 1, &line,
+      format!("Found {} distances for {} race times", distances.len(), times.len())
+    ));
   }
 
-  Ok(output)
+  Ok(times.into_iter().zip(distances).collect())
 }
 
 
-fn parse_line(lines: &mut io::Lines<io::BufReader<File>>) -> Option<Vec<u64>>{
-  let Ok(line) = lines.next()? else {
-      return None;
+fn parse_line<B: BufRead>(
+  lines: &mut NumberedLines<B>, line_no: usize, name: &str
+) -> Result<(Vec<BigInt>, (usize, String))> {
+  let Some((_, l)) = lines.next() else {
+      return Err(Error::parse(line_no, 1, "", format!("Missing line of {name}")));
   };
-  let chars_vec = line.chars().collect::<Vec<char>>();
-  let mut chars = chars_vec.iter().peekable();
-  let mut seeds = Vec::new();
-
-  loop {
-      let Some(n) = parse_num(&mut chars, true) else {
-          break;
-      };
+  let line = l?;
+  let mut numbers = Vec::new();
+  let mut digits = String::new();
+
+  /* Numbers can be any length, so collect their digits before converting */
+  for ch in line.chars().chain([' ']) {
+      if ch.is_ascii_digit() {
+          digits.push(ch);
+      } else if let Ok(n) = digits.parse() {
+          numbers.push(n);
+          digits.clear();
+      }
+  }
 
-      seeds.push(n as u64);
+  if numbers.is_empty() {
+      return Err(Error::parse(line_no, 1, &line, format!("Expected a list of {name}")));
   }
 
-  Some(seeds)
+  Ok((numbers, (line_no, line)))
 }
 
+#[cfg(test)]
+mod tests {
+  use super::{Day6, winning_hold_times};
+  use crate::days::solve_example;
+  use num::BigInt;
+  use crate::days::Part::{P1, P2};
+
+  const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
+
+  #[test]
+  fn part1_example() {
+    assert_eq!(solve_example(&Day6, EXAMPLE, P1), 288.into());
+  }
+
+  #[test]
+  fn part2_example() {
+    assert_eq!(solve_example(&Day6, EXAMPLE, P2), 71503.into());
+  }
+
+  #[test]
+  fn closed_form_matches_counting() {
+    for time in 0..40_i64 {
+      for dist in 0..(time * time / 4 + 2) {
+        let counted = (0..=time).filter(|h| h * (time - h) > dist).count();
 
-fn length(n: u64, base: u64) -> u64 {
-  let mut power = base;
-  let mut count = 1;
-  while n >= power {
-      count += 1;
-      if let Some(new_power) = power.checked_mul(base) {
-          power = new_power;
-      } else {
-          break;
+        assert_eq!(winning_hold_times(&time.into(), &dist.into()), counted.into());
       }
+    }
+
+    /* Far beyond u64, every hold except none and all of it wins a zero length race */
+    let time = BigInt::from(10).pow(40);
+    assert_eq!(winning_hold_times(&time, &BigInt::from(0)), time - 1);
   }
-  count
-}
\ No newline at end of file
+}
@@ -1,234 +1,318 @@
-use crate::days::Part;
-use crate::days::{read_lines, parse_num};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::days::{collect_lines, parse_num, Part};
+use crate::log::info;
 
+use std::io::BufRead;
 use std::cmp::Ordering;
 
 
-#[derive(Debug, Eq, PartialOrd, Ord, PartialEq)]
-enum HandType {
-    FiveOfAKind,
-    FourOfAKind,
-    FullHouse,
-    ThreeOfAKind,
-    TwoPair,
-    OnePair,
-    HighCard
+/* A kind of hand, matched when the hand has groups of at least these sizes */
+#[derive(Debug, Eq, PartialEq)]
+struct Category {
+    name: &'static str,
+    pattern: &'static [usize]
 }
 
-#[derive(Debug, Eq, PartialOrd, Ord, PartialEq, Copy, Clone)]
-enum CardType {
-    Ace,
-    King,
-    Queen,
-    Jack,
-    Ten,
-    Nine,
-    Eight,
-    Seven,
-    Six,
-    Five,
-    Four,
-    Three,
-    Two,
-    Joker
+/*
+ * How hands are dealt and ranked. Card labels run from weakest to
+ * strongest and break ties between hands of the same category.
+ * Wildcards stand in for whichever labels make the strongest category,
+ * but keep their own place in the order when breaking ties.
+ */
+#[derive(Debug)]
+struct Rules {
+    order: Vec<char>,
+    wildcards: Vec<char>,
+    hand_size: usize,
+    /* Weakest first */
+    categories: &'static [Category]
 }
 
-#[derive(Debug, Eq)]
+#[derive(Debug, Eq, PartialEq)]
 struct Hand {
-    cards: [CardType; 5],
-    hand_type: HandType,
+    cards: Vec<char>,
+    /* Index into the rule's categories, higher is stronger */
+    category: usize,
+    /* Position of each card in the rule's order */
+    strengths: Vec<usize>,
     bid: u64
 }
 
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-  match part {
-      Part::P1 => part1(file_name, false),
-      Part::P2 => part1(file_name, true),
-  }
+const CAMEL_CARDS: [Category; 7] = [
+    Category { name: "High card", pattern: &[] },
+    Category { name: "One pair", pattern: &[2] },
+    Category { name: "Two pair", pattern: &[2, 2] },
+    Category { name: "Three of a kind", pattern: &[3] },
+    Category { name: "Full house", pattern: &[3, 2] },
+    Category { name: "Four of a kind", pattern: &[4] },
+    Category { name: "Five of a kind", pattern: &[5] },
+];
+
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<String>;
+
+    const DAY: u32 = 7;
+    const TITLE: &'static str = "Camel Cards";
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        collect_lines(input)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        total_winnings(input, &Rules::standard()).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        total_winnings(input, &Rules::jokers()).map(Answer::from)
+    }
+
+    fn explain(&self, input: &Self::Input, part: Part, query: &str) -> Option<Result<String>> {
+        let rules = match part {
+            Part::P1 => Rules::standard(),
+            Part::P2 => Rules::jokers(),
+        };
+
+        Some(explain(input, &rules, query))
+    }
 }
 
 
-fn part1(file_name: &str, use_joker: bool) -> Result<u64, &'static str> {
-    let hands = parse_hands(file_name, use_joker)?;
+fn total_winnings(lines: &[String], rules: &Rules) -> Result<u64> {
+    let hands = parse_hands(lines, rules)?;
     let mut output = 0;
-    let length = hands.len() as u64;
 
     for (i, h) in hands.iter().enumerate() {
-        println!("{:?}", h);
-        output += h.bid * (length - i as u64);
+        info!("{} {} {}", i + 1, h.cards.iter().collect::<String>(), rules.categories[h.category].name);
+        output += h.bid * (i as u64 + 1);
     }
-  
+
     Ok(output)
 }
 
 
+impl Rules {
+    fn new(order: &str, wildcards: &str, hand_size: usize, categories: &'static [Category]) -> Rules {
+        Rules {
+            order: order.chars().collect(),
+            wildcards: wildcards.chars().collect(),
+            hand_size,
+            categories
+        }
+    }
 
-fn parse_hands(file_name: &str, use_joker: bool) -> Result<Vec<Hand>, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to read file");
-    };
-
-    let mut output = Vec::new();
-  
-    for l in lines {
-        let Ok(line) = l else {
-            break;
-        };
+    fn standard() -> Rules {
+        Rules::new("23456789TJQKA", "", 5, &CAMEL_CARDS)
+    }
 
-        let Some(hand) = parse_hand(&line, use_joker) else {
-            return Err("Failed to parse hand");
-        };
+    /* J becomes a joker, the weakest card on its own */
+    fn jokers() -> Rules {
+        Rules::new("J23456789TQKA", "J", 5, &CAMEL_CARDS)
+    }
 
-        output.push(hand);
+    fn strength(&self, card: char) -> Option<usize> {
+        self.order.iter().position(|&c| c == card)
     }
 
-    output.sort();
-  
-    Ok(output)
-}
+    /* Sizes and labels of the groups of matching plain cards, biggest first */
+    fn groups(&self, cards: &[char]) -> (Vec<(usize, char)>, usize) {
+        let mut groups: Vec<(usize, char)> = Vec::new();
+        let mut wild = 0;
 
+        for &card in cards {
+            if self.wildcards.contains(&card) {
+                wild += 1;
+                continue;
+            }
 
-fn parse_hand(line: &str, use_joker: bool) -> Option<Hand> {
-    let chars_vec = line.chars().collect::<Vec<char>>();
-    let mut chars = chars_vec.iter().peekable();
+            match groups.iter_mut().find(|(_, label)| *label == card) {
+                Some((size, _)) => *size += 1,
+                None => groups.push((1, card)),
+            };
+        }
+
+        /* Ties go to the stronger label, which is the one wildcards should copy */
+        groups.sort_by(|a, b| b.0.cmp(&a.0).then(self.strength(b.1).cmp(&self.strength(a.1))));
+
+        (groups, wild)
+    }
 
     /*
-     * 2 -> 0,
-     * 3 -> 1,
-     * ...
-     * 9 -> 7,
-     * T -> 8,
-     * J -> 9,
-     * Q -> 10,
-     * K -> 11,
-     * A -> 12
+     * The strongest category the wildcards can reach. Pairing the biggest
+     * groups with the biggest parts of a pattern needs the fewest
+     * wildcards to fill the gaps.
      */
+    fn classify(&self, cards: &[char]) -> usize {
+        let (groups, wild) = self.groups(cards);
 
-    let mut count: [u64; 13] = [0; 13];
-    let mut hand: [CardType; 5] = [CardType::Two; 5];
-    let mut joker_count = 0;
-
-    for j in 0..5 {
-        let (i, c) = match chars.peek() {
-            Some('2') => (0, CardType::Two),
-            Some('3') => (1, CardType::Three),
-            Some('4') => (2, CardType::Four),
-            Some('5') => (3, CardType::Five),
-            Some('6') => (4, CardType::Six),
-            Some('7') => (5, CardType::Seven),
-            Some('8') => (6, CardType::Eight),
-            Some('9') => (7, CardType::Nine),
-            Some('T') => (8, CardType::Ten),
-            Some('J') => (9, if use_joker { CardType::Joker } else { CardType::Jack } ),
-            Some('Q') => (10, CardType::Queen),
-            Some('K') => (11, CardType::King),
-            Some('A') => (12, CardType::Ace),
-            _ => return None,
-        };
-        chars.next();
-
-        if c == CardType::Joker {
-            joker_count += 1;
-        }
+        (0..self.categories.len()).rev()
+            .find(|&i| self.shortfall(&groups, self.categories[i].pattern) <= wild)
+            .unwrap_or(0)
+    }
 
-        count[i as usize] += 1;
-        hand[j as usize] = c;
+    fn shortfall(&self, groups: &[(usize, char)], pattern: &[usize]) -> usize {
+        pattern.iter().enumerate()
+            .map(|(i, &need)| need.saturating_sub(groups.get(i).map_or(0, |g| g.0)))
+            .sum()
     }
 
-    let bid = parse_num(&mut chars, true)? as u64;
-    let hand_type = determin_type(&count, joker_count);
+    /* The label each wildcard in cards is played as, in the order they appear */
+    fn substitutions(&self, cards: &[char]) -> Vec<char> {
+        let (groups, wild) = self.groups(cards);
+        let pattern = self.categories[self.classify(cards)].pattern;
 
-    Some(Hand {
-        cards: hand,
-        hand_type,
-        bid: bid
-    })
-}
+        /* Unused labels, strongest first, for patterns needing new groups */
+        let mut unused = self.order.iter().rev()
+            .filter(|c| !self.wildcards.contains(c) && !groups.iter().any(|g| g.1 == **c));
 
+        let mut labels = Vec::new();
 
-fn determin_type(count: &[u64; 13], joker_count: u64) -> HandType {
-    let mut has_four = false;
-    let mut has_three = false;
-    let mut has_two_pair = false;
-    let mut has_pair = false;
-
-    for c in count {
-        match *c {
-            5 => return HandType::FiveOfAKind,
-            4 => has_four = true,
-            3 => has_three = true,
-            2 => if has_pair {
-                has_two_pair = true; 
-            } else {
-                has_pair = true
-            },
-            _ => ()
-        };
-    }
+        for (i, &need) in pattern.iter().enumerate() {
+            let (size, label) = match groups.get(i) {
+                Some(&group) => group,
+                None => (0, *unused.next().unwrap_or(&'?')),
+            };
 
-    if has_four {
-        if joker_count == 1 || joker_count == 4 {
-            return HandType::FiveOfAKind;
-        } else {
-            return HandType::FourOfAKind;
+            labels.extend(std::iter::repeat_n(label, need.saturating_sub(size)));
         }
+
+        /* Any left over join the biggest group, which can only help */
+        let spare = labels.first().or(groups.first().map(|g| &g.1)).copied();
+        let spare = spare.or(unused.next().copied()).unwrap_or('?');
+
+        labels.resize(wild, spare);
+        labels
     }
 
-    if has_three && !has_pair {
-        if joker_count == 3 || joker_count == 1 {
-            return HandType::FourOfAKind;
-        } else {
-            return HandType::ThreeOfAKind;
+    fn parse_cards(&self, line_no: usize, line: &str) -> Result<Vec<char>> {
+        let cards: Vec<char> = line.chars().take(self.hand_size).collect();
+
+        for (j, card) in cards.iter().enumerate() {
+            if self.strength(*card).is_none() {
+                return Err(Error::parse(line_no, j + 1, line, "Expected a card label"));
+            }
         }
-    } 
 
-    if has_three && has_pair {
-        if joker_count == 3 || joker_count == 2 {
-            return HandType::FiveOfAKind;
-        } else {
-            return HandType::FullHouse;
+        if cards.len() < self.hand_size {
+            return Err(Error::parse(
+                line_no, cards.len() + 1, line, format!("Expected {} cards in a hand", self.hand_size)
+            ));
         }
-    }
 
-    if has_two_pair {
-        if joker_count == 2 {
-            return HandType::FourOfAKind;
-        } else if joker_count == 1 {
-            return HandType::FullHouse;
-        } else {
-            return HandType::TwoPair;
+        /* Otherwise a longer run of labels would be cut down to a hand */
+        if line.chars().nth(self.hand_size).is_some_and(|ch| !ch.is_whitespace()) {
+            return Err(Error::parse(
+                line_no, self.hand_size + 1, line, format!("Expected a space after {} cards", self.hand_size)
+            ));
         }
+
+        Ok(cards)
     }
 
-    if has_pair {
-        if joker_count == 1 || joker_count == 2 {
-            return HandType::ThreeOfAKind;
-        } else {
-            return HandType::OnePair;
+    fn hand(&self, cards: Vec<char>, bid: u64) -> Hand {
+        Hand {
+            category: self.classify(&cards),
+            strengths: cards.iter().filter_map(|&c| self.strength(c)).collect(),
+            cards,
+            bid
         }
     }
+}
+
+
+/* Weakest first, so a hand's rank is its position plus one */
+fn parse_hands(lines: &[String], rules: &Rules) -> Result<Vec<Hand>> {
+    let mut output = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        output.push(parse_hand(i + 1, line, rules)?);
+    }
+
+    output.sort();
+
+    Ok(output)
+}
+
+
+fn parse_hand(line_no: usize, line: &str, rules: &Rules) -> Result<Hand> {
+    let cards = rules.parse_cards(line_no, line)?;
+
+    let chars_vec = line.chars().skip(rules.hand_size).collect::<Vec<char>>();
+    let mut chars = chars_vec.iter().peekable();
+
+    let Some(bid) = parse_num(&mut chars, true) else {
+        return Err(Error::parse(line_no, rules.hand_size + 1, line, "Missing bid after hand"));
+    };
+
+    Ok(rules.hand(cards, bid as u64))
+}
+
+
+/*
+ * Describes how a hand is classified and where it would rank among the
+ * puzzle's hands, including what decides it against its neighbours.
+ */
+fn explain(lines: &[String], rules: &Rules, query: &str) -> Result<String> {
+    let hands = parse_hands(lines, rules)?;
+    let query = query.trim();
+
+    let cards = match rules.parse_cards(1, query) {
+        Ok(cards) => cards,
+        Err(Error::Parse { column, reason, .. }) => return Err(Error::invalid_state(
+            format!("query '{query}' is not a hand: {reason} at column {column}")
+        )),
+        Err(reason) => return Err(reason),
+    };
+
+    let hand = rules.hand(cards, 0);
+    let name = |hand: &Hand| rules.categories[hand.category].name;
+    let label = |hand: &Hand| hand.cards.iter().collect::<String>();
+
+    let mut text = format!("{}: {}", label(&hand), name(&hand));
+
+    let wildcards: Vec<char> = hand.cards.iter().copied().filter(|c| rules.wildcards.contains(c)).collect();
+
+    if !wildcards.is_empty() {
+        let played: Vec<String> = wildcards.iter().zip(rules.substitutions(&hand.cards))
+            .map(|(wild, card)| format!("{wild} as {card}"))
+            .collect();
+
+        text += &format!(", playing {}", played.join(", "));
+    }
+
+    /* Hands already in the input with the same cards are left out of the neighbours */
+    let others: Vec<&Hand> = hands.iter().filter(|other| other.cards != hand.cards).collect();
+    let below = others.partition_point(|other| **other < hand);
+
+    text += &format!("\n  Rank {} of {}", below + 1, others.len() + 1);
 
-    if joker_count == 1 {
-        return HandType::OnePair;
-    } else {
-        return HandType::HighCard;
+    for neighbour in [below.checked_sub(1).map(|i| others[i]), others.get(below).copied()].into_iter().flatten() {
+        let verb = if hand > *neighbour { "Beats" } else { "Loses to" };
+
+        text += &format!("\n  {verb} {} ({}): ", label(neighbour), name(neighbour));
+
+        match (0..hand.cards.len()).find(|&i| hand.strengths[i] != neighbour.strengths[i]) {
+            _ if hand.category != neighbour.category => text += "decided by hand type",
+            Some(i) => text += &format!(
+                "same type, decided at card {} with {} against {}", i + 1, hand.cards[i], neighbour.cards[i]
+            ),
+            None => text += "identical ranks",
+        };
     }
+
+    Ok(text)
 }
 
 
 impl Ord for Hand {
     fn cmp(&self, other: &Self) -> Ordering {
-        if self.hand_type != other.hand_type {
-            return self.hand_type.cmp(&other.hand_type);
-        }
-
-        for i in 0..5 {
-            if self.cards[i] != other.cards[i] {
-                return self.cards[i].cmp(&other.cards[i]);
-            }
-        }
-        self.cards.cmp(&other.cards)
+        self.category.cmp(&other.category)
+            .then_with(|| self.strengths.cmp(&other.strengths))
     }
 }
 
@@ -238,10 +322,130 @@ impl PartialOrd for Hand {
     }
 }
 
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self.hand_type == other.hand_type && (
-            self.cards == other.cards
-        )
+#[cfg(test)]
+mod tests {
+    use super::{Category, Day7, Rules, CAMEL_CARDS, explain, parse_hand};
+    use crate::error::Error;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day7, EXAMPLE, P1), 6440.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day7, EXAMPLE, P2), 5905.into());
     }
-}
\ No newline at end of file
+
+    /* Every multiset of hand_size cards, as sorted label indices */
+    fn hands(labels: usize, hand_size: usize) -> Vec<Vec<usize>> {
+        if hand_size == 0 {
+            return vec![vec![]];
+        }
+
+        hands(labels, hand_size - 1).into_iter()
+            .flat_map(|hand| {
+                let from = hand.last().copied().unwrap_or(0);
+                (from..labels).map(move |label| [hand.clone(), vec![label]].concat())
+            })
+            .collect()
+    }
+
+    /* Tries every way of replacing the wildcards with plain labels */
+    fn brute_force(rules: &Rules, cards: &[char]) -> usize {
+        let plain: Vec<char> = rules.order.iter().copied().filter(|c| !rules.wildcards.contains(c)).collect();
+        let fixed: Vec<char> = cards.iter().copied().filter(|c| !rules.wildcards.contains(c)).collect();
+        let no_wildcards = Rules { wildcards: vec![], ..Rules::new("", "", rules.hand_size, rules.categories) };
+
+        hands(plain.len(), cards.len() - fixed.len()).iter()
+            .map(|extra| {
+                let hand: Vec<char> = fixed.iter().copied().chain(extra.iter().map(|&i| plain[i])).collect();
+                no_wildcards.classify(&hand)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn cross_check(rules: &Rules) {
+        for hand in hands(rules.order.len(), rules.hand_size) {
+            let cards: Vec<char> = hand.iter().map(|&i| rules.order[i]).collect();
+            let category = rules.classify(&cards);
+
+            assert_eq!(category, brute_force(rules, &cards), "{cards:?}");
+
+            /* Playing the reported substitutions reaches the same category */
+            let mut played = cards.clone();
+            let mut substitutions = rules.substitutions(&cards).into_iter();
+
+            for card in played.iter_mut().filter(|c| rules.wildcards.contains(c)) {
+                *card = substitutions.next().unwrap();
+            }
+
+            assert_eq!(brute_force(rules, &played), category, "{cards:?} as {played:?}");
+        }
+    }
+
+    #[test]
+    fn wildcards_match_brute_force() {
+        cross_check(&Rules::standard());
+        cross_check(&Rules::jokers());
+    }
+
+    #[test]
+    fn custom_rule_sets() {
+        static SIX_CARDS: [Category; 5] = [
+            Category { name: "High card", pattern: &[] },
+            Category { name: "Pair", pattern: &[2] },
+            Category { name: "Three pair", pattern: &[2, 2, 2] },
+            Category { name: "Two triples", pattern: &[3, 3] },
+            Category { name: "Six of a kind", pattern: &[6] },
+        ];
+
+        let rules = Rules::new("J23456789TQKA", "J2", 5, &CAMEL_CARDS);
+        cross_check(&rules);
+        assert_eq!(CAMEL_CARDS[rules.classify(&['J', '2', 'A', 'K', 'Q'])].name, "Three of a kind");
+
+        let rules = Rules::new("9TJQKA", "J", 6, &SIX_CARDS);
+        cross_check(&rules);
+        assert_eq!(SIX_CARDS[rules.classify(&['9', '9', 'T', 'T', 'K', 'J'])].name, "Three pair");
+    }
+
+    #[test]
+    fn explains_a_hand() {
+        let lines: Vec<String> = EXAMPLE.lines().map(String::from).collect();
+        let text = explain(&lines, &Rules::jokers(), "KTJJT").unwrap();
+
+        assert_eq!(text, "\
+KTJJT: Four of a kind, playing J as T, J as T
+  Rank 5 of 5
+  Beats QQQJA (Four of a kind): same type, decided at card 1 with K against Q");
+
+        let text = explain(&lines, &Rules::standard(), "KTJJT").unwrap();
+        assert!(text.starts_with("KTJJT: Two pair\n  Rank 2 of 5"));
+        assert!(text.contains("Loses to KK677 (Two pair): same type, decided at card 2 with T against K"));
+
+        let Err(Error::InvalidState(reason)) = explain(&lines, &Rules::jokers(), "KTJ") else {
+            panic!("expected an invalid query");
+        };
+        assert_eq!(reason, "query 'KTJ' is not a hand: Expected 5 cards in a hand at column 4");
+    }
+
+    #[test]
+    fn rejects_hands_of_the_wrong_size() {
+        let Err(Error::Parse { line, column, .. }) = parse_hand(3, "32T3KK 765", &Rules::standard()) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((line, column), (3, 6));
+        assert!(parse_hand(3, "32T3K\t765", &Rules::standard()).is_ok());
+    }
+}
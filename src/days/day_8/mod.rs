@@ -1,255 +1,646 @@
-use crate::days::Part;
-use crate::days::{read_lines};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::days::{NumberedLines, Part};
+use crate::log::{info, debug, trace};
 
-use std::fs::File;
-use std::io::{self};
+use std::io::BufRead;
 use std::iter::Peekable;
-use num::integer::lcm;
+use std::collections::{HashMap, HashSet};
+use num::{BigInt, One, Zero};
+use num::integer::{ExtendedGcd, Integer};
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 enum Action {
     Left,
     Right
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
-struct Node {
-    id: u64,
-    left_id: u64,
-    right_id: u64,
-    is_end: bool
+/*
+ * The network with every node name interned to a number, in the order
+ * the names first appear. Names are kept so they can be shown again.
+ */
+#[derive(Debug, Default)]
+struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    /* The left and right node of each node */
+    edges: Vec<(usize, usize)>
+}
+
+pub struct Input {
+    path: Vec<Action>,
+    graph: Graph
+}
+
+/* Which nodes a walk starts from, and which nodes it stops at */
+struct Walk {
+    is_start: fn(&str) -> bool,
+    is_end: fn(&str) -> bool
 }
 
-static START_ID: u64 = 0;
-static END_ID: u64 = 17575;
+const CAMEL: Walk = Walk {
+    is_start: |name| name == "AAA",
+    is_end: |name| name == "ZZZ"
+};
+
+const GHOSTS: Walk = Walk {
+    is_start: |name| name.ends_with('A'),
+    is_end: |name| name.ends_with('Z')
+};
+
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Input;
 
+    const DAY: u32 = 8;
+    const TITLE: &'static str = "Haunted Wasteland";
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_input(input)
     }
-  }
-  
 
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let (path, graph, _) = parse_input(file_name)?;
-    
-    let mut i = 0;
-    let mut amount_walked = 0;
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input, &CAMEL).map(Answer::from)
+    }
 
-    let mut current_node = graph[START_ID as usize].unwrap();
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input, &GHOSTS).map(Answer::from)
+    }
 
+    /* Draws the network from the named node, or all of it */
+    fn explain(&self, input: &Self::Input, part: Part, query: &str) -> Option<Result<String>> {
+        let walk = match part {
+            Part::P1 => &CAMEL,
+            Part::P2 => &GHOSTS,
+        };
 
-    loop {
-        let Some(node) = (match &path[i % path.len()] {
-            Action::Left => &graph[current_node.left_id as usize],
-            Action::Right => &graph[current_node.right_id as usize]
-        }) else {
-            println!("Failed to find node from {}", current_node.id);
-            break;
+        Some(to_dot(input, walk, (query != "all").then_some(query)))
+    }
+}
+
+
+fn part1(input: &Input, walk: &Walk) -> Result<u64> {
+    let Some(&start) = input.graph.matching(walk.is_start).first() else {
+        return Err(Error::invalid_state("Missing a start node to walk from"));
+    };
+
+    let arrivals = Arrivals::walk(input, start, walk);
+
+    match arrivals.first() {
+        Some(steps) => Ok(steps),
+        None => Err(Error::unsolvable(
+            format!("Walking from {} never reaches an end node", input.graph.name(start))
+        )),
+    }
+}
+
+
+fn part2(input: &Input, walk: &Walk) -> Result<BigInt> {
+    debug!("{:?}", input.path);
+
+    let start_nodes = input.graph.matching(walk.is_start);
+
+    if start_nodes.is_empty() {
+        return Err(Error::invalid_state("Missing start nodes to walk from"));
+    }
+
+    let ghosts: Vec<Arrivals> = start_nodes.iter()
+        .map(|&start| {
+            let arrivals = Arrivals::walk(input, start, walk);
+            info!("node: {}, {:?}", input.graph.name(start), arrivals);
+            arrivals
+        })
+        .collect();
+
+    match first_together(&ghosts) {
+        Some(steps) => Ok(steps),
+        None => Err(Error::unsolvable("The ghosts never stand on end nodes at the same time")),
+    }
+}
+
+
+/*
+ * Every step count at which one walker stands on an end node. The walk
+ * is decided by its node and how far along the path it is, so once that
+ * pair repeats it loops forever. Arrivals before the loop happen once,
+ * arrivals within it come back every loop_len steps.
+ */
+#[derive(Debug, Eq, PartialEq)]
+struct Arrivals {
+    once: Vec<u64>,
+    loop_start: u64,
+    loop_len: u64,
+    repeating: Vec<u64>
+}
+
+
+impl Arrivals {
+    fn walk(input: &Input, start: usize, walk: &Walk) -> Arrivals {
+        let Input { path, graph } = input;
+
+        /* The step each (node, path position) was first reached at */
+        let mut seen = vec![None; graph.len() * path.len()];
+        let mut arrivals = Vec::new();
+        let mut node = start;
+        let mut steps = 0;
+
+        let loop_start = loop {
+            let state = node * path.len() + steps as usize % path.len();
+
+            if let Some(first) = seen[state] {
+                break first;
+            }
+
+            seen[state] = Some(steps);
+
+            if (walk.is_end)(graph.name(node)) {
+                arrivals.push(steps);
+            }
+
+            node = graph.next(node, path[steps as usize % path.len()]);
+            trace!("{}", graph.name(node));
+            steps += 1;
         };
 
-        amount_walked += 1;
-        i += 1;
-        println!("{:?}", node);
+        let loop_len = steps - loop_start;
+        let split = arrivals.partition_point(|&steps| steps < loop_start);
+        let mut repeating = arrivals.split_off(split);
+
+        /*
+         * Starting on an end node is not an arrival, but when the start is
+         * part of the loop the walk arrives there again a loop later.
+         */
+        if arrivals.first() == Some(&0) {
+            arrivals.remove(0);
+        }
 
+        if repeating.first() == Some(&0) {
+            repeating.remove(0);
+            repeating.push(loop_len);
+        }
 
-        if node.id == END_ID {
-            break;
+        Arrivals { once: arrivals, loop_start, loop_len, repeating }
+    }
+
+    /* The steps taken once round the loop, as (from, to) node pairs */
+    fn loop_edges(&self, input: &Input, start: usize) -> HashSet<(usize, usize)> {
+        let Input { path, graph } = input;
+        let mut edges = HashSet::new();
+        let mut node = start;
+
+        for steps in 0..self.loop_start + self.loop_len {
+            let next = graph.next(node, path[steps as usize % path.len()]);
+
+            if steps >= self.loop_start {
+                edges.insert((node, next));
+            }
+
+            node = next;
         }
 
-        current_node = *node;
+        edges
+    }
+
+    fn first(&self) -> Option<u64> {
+        self.once.first().or(self.repeating.first()).copied()
+    }
+
+    fn contains(&self, steps: u64) -> bool {
+        if steps < self.loop_start {
+            return self.once.contains(&steps);
+        }
+
+        self.repeating.iter().any(|&first| steps >= first && (steps - first).is_multiple_of(self.loop_len))
     }
-  
-    Ok(amount_walked)
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let (path, graph, start_nodes) = parse_input(file_name)?;
+/*
+ * The first step at which every walker stands on an end node. Before the
+ * last walker settles into its loop only its one-off arrivals can match.
+ * After that every walker repeats, so each choice of one repeating
+ * arrival per walker is a set of congruences to solve together.
+ */
+fn first_together(walkers: &[Arrivals]) -> Option<BigInt> {
+    let settled = walkers.iter().max_by_key(|walker| walker.loop_start)?;
+
+    if let Some(&steps) = settled.once.iter().find(|&&steps| walkers.iter().all(|w| w.contains(steps))) {
+        return Some(BigInt::from(steps));
+    }
 
-    println!("{:?}", path);
-    
-    let mut current_nodes = Vec::new();
+    let mut solutions = vec![(BigInt::zero(), BigInt::one())];
 
-    for id in start_nodes {
-        let Some(_node) = graph[id as usize] else {
-            return Err("Failed to find start node");
-        };
-        current_nodes.push((id as usize, 0 as u64));
+    for walker in walkers {
+        let loop_len = BigInt::from(walker.loop_len);
+        let mut next: Vec<(BigInt, BigInt)> = solutions.iter()
+            .flat_map(|solution| walker.repeating.iter()
+                .filter_map(|&first| crt(solution, (&BigInt::from(first), &loop_len))))
+            .collect();
+
+        next.sort();
+        next.dedup();
+        solutions = next;
     }
 
-    println!("{:?}", current_nodes);
+    /* The smallest step from the settled point onwards matching each solution, never step 0 */
+    let from = BigInt::from(settled.loop_start.max(1));
+
+    solutions.into_iter()
+        .map(|(residue, modulus)| &from + (residue - &from).mod_floor(&modulus))
+        .min()
+}
 
-    let current_nodes_len = current_nodes.len();
 
+/*
+ * Combines x = a (mod m) and x = b (mod n) into a single congruence,
+ * when the moduli need not be coprime. None if they contradict. The
+ * combined modulus is the lcm of every loop so far, which can outgrow
+ * any fixed width integer.
+ */
+fn crt((a, m): &(BigInt, BigInt), (b, n): (&BigInt, &BigInt)) -> Option<(BigInt, BigInt)> {
+    let ExtendedGcd { gcd, x, .. } = m.extended_gcd(n);
+    let diff = b - a;
 
-    for cn_i in 0..current_nodes_len {
-        let mut i = 0;
-        let mut amount_walked = 0;
+    if !(&diff % &gcd).is_zero() {
+        return None;
+    }
 
-        loop {
-            let action = &path[i % path.len()];
-        
-            let (cn_id, _cn_s) = current_nodes[cn_i];
+    let modulus = m / &gcd * n;
+    let k = (diff / &gcd * x).mod_floor(&(n / &gcd));
 
-            let node_id = match action {
-                Action::Left => graph[cn_id].unwrap().left_id as usize,
-                Action::Right => graph[cn_id].unwrap().right_id as usize
+    Some(((a + m * k).mod_floor(&modulus), modulus))
+}
+
+
+/*
+ * The network in Graphviz's DOT language, limited to what can be reached
+ * from one node if given. Start nodes are boxed and end nodes circled
+ * twice. Each walker starting within the drawing has the edges of the
+ * loop it falls into coloured, with the loop described in the label.
+ */
+fn to_dot(input: &Input, walk: &Walk, from: Option<&str>) -> Result<String> {
+    const COLOURS: [&str; 6] = ["red", "blue", "darkgreen", "orange", "purple", "brown"];
+    let graph = &input.graph;
+
+    let drawn = match from {
+        Some(name) => {
+            let Some(&id) = graph.ids.get(name) else {
+                return Err(Error::invalid_state(format!("No node named {name}")));
             };
+            graph.reachable(id)
+        },
+        None => vec![true; graph.len()],
+    };
 
-            i += 1;
-            amount_walked += 1;
+    let mut loops: HashMap<(usize, usize), &str> = HashMap::new();
+    let mut labels = Vec::new();
 
-            current_nodes[cn_i] = (node_id, amount_walked);
+    let starts = graph.matching(walk.is_start).into_iter().filter(|&id| drawn[id]);
 
-            if graph[node_id].unwrap().is_end {
-                break;
-            }
+    for (start, colour) in starts.zip(COLOURS.iter().cycle()) {
+        let arrivals = Arrivals::walk(input, start, walk);
+
+        for edge in arrivals.loop_edges(input, start) {
+            loops.entry(edge).or_insert(colour);
         }
 
-        println!("node: {:?}, {}", graph[current_nodes[cn_i].0], current_nodes[cn_i].1);
-    }    
+        labels.push(format!(
+            "{} ({colour}) loops every {} steps from step {}, on end nodes at steps {:?} then {:?}",
+            graph.name(start), arrivals.loop_len, arrivals.loop_start, arrivals.once, arrivals.repeating
+        ));
+    }
+
+    let mut text = String::from("digraph network {\n");
+    text += &format!("    label=\"{}\";\n    labeljust=l;\n", labels.join("\\l"));
+
+    for id in (0..graph.len()).filter(|&id| drawn[id]) {
+        let name = graph.name(id);
+
+        if (walk.is_start)(name) {
+            text += &format!("    \"{name}\" [shape=box, style=bold];\n");
+        } else if (walk.is_end)(name) {
+            text += &format!("    \"{name}\" [shape=doublecircle];\n");
+        }
+
+        let (left, right) = graph.edges[id];
+        let edges = match left == right {
+            true => vec![(left, "LR")],
+            false => vec![(left, "L"), (right, "R")],
+        };
 
-    let mut result = 1;
+        for (to, label) in edges {
+            let style = match loops.get(&(id, to)) {
+                Some(colour) => format!(", color={colour}, penwidth=2"),
+                None => String::new(),
+            };
 
-    for (_, l) in &current_nodes {
-        println!("{}", l);
-        result = lcm(result, *l as u64);
+            text += &format!("    \"{name}\" -> \"{}\" [label=\"{label}\"{style}];\n", graph.name(to));
+        }
     }
 
-  
-    Ok(result as u64)
+    text += "}";
+
+    Ok(text)
 }
 
 
-fn parse_input(file_name: &str) -> Result<(Vec<Action>, [Option<Node>; 17576], Vec<u64>), &'static str> {
-    let Ok(mut lines) = read_lines(file_name) else {
-        return Err("Failed to read file");
-    };
+impl Graph {
+    fn len(&self) -> usize {
+        self.names.len()
+    }
 
-    let Some(path) = parse_path(&mut lines) else {
-        return Err("Failed to parse path");
-    };
+    fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    fn next(&self, id: usize, action: Action) -> usize {
+        match action {
+            Action::Left => self.edges[id].0,
+            Action::Right => self.edges[id].1,
+        }
+    }
 
+    /* Marks every node that can be walked to from start, start included */
+    fn reachable(&self, start: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
 
-    lines.next(); /* Skip emptyy line */ 
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut seen[id], true) {
+                continue;
+            }
 
-    let Some((graph, start_nodes)) = parse_graph(&mut lines) else {
-        return Err("Failed to parse graph");
-    };
+            stack.extend([self.edges[id].0, self.edges[id].1]);
+        }
+
+        seen
+    }
+
+    /* Every node whose name is accepted, in order of appearance */
+    fn matching(&self, accept: fn(&str) -> bool) -> Vec<usize> {
+        (0..self.len()).filter(|&id| accept(self.name(id))).collect()
+    }
 
+    fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
 
-    Ok((path, graph, start_nodes))
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
 }
 
 
+fn parse_input<B: BufRead>(input: B) -> Result<Input> {
+    let mut lines = input.lines().enumerate();
 
-fn parse_path(lines: &mut io::Lines<io::BufReader<File>>) -> Option<Vec<Action>> {
-    let Ok(line) = lines.next()? else {
-        return None;
+    let path = parse_path(&mut lines)?;
+
+    lines.next(); /* Skip emptyy line */ 
+
+    let graph = parse_graph(&mut lines)?;
+
+    Ok(Input { path, graph })
+}
+
+
+
+fn parse_path<B: BufRead>(lines: &mut NumberedLines<B>) -> Result<Vec<Action>> {
+    let Some((_, l)) = lines.next() else {
+        return Err(Error::parse(1, 1, "", "Missing path line"));
     };
+    let line = l?;
     let mut actions = Vec::new();
 
-    for c in line.chars() {
+    for (i, c) in line.chars().enumerate() {
         match c {
             'L' => actions.push(Action::Left),
             'R' => actions.push(Action::Right),
+            _ if actions.is_empty() => return Err(Error::parse(
+                1, i + 1, &line, "Expected path of L and R actions"
+            )),
             _ => break,
         };
     }
 
+    if actions.is_empty() {
+        return Err(Error::parse(1, 1, &line, "Empty path"));
+    }
 
-    Some(actions)
+    Ok(actions)
 }
 
 
-fn parse_graph(lines: &mut io::Lines<io::BufReader<File>>) -> Option<([Option<Node>; 17576], Vec<u64>)> {
-    let mut start_nodes = Vec::new();
-    let mut graph: [Option<Node>; 17576] = [None; 17576];
+fn parse_graph<B: BufRead>(lines: &mut NumberedLines<B>) -> Result<Graph> {
+    let mut graph = Graph::default();
+    /* Nodes can be mentioned before they are defined, so edges are filled in as found */
+    let mut edges: Vec<Option<(usize, usize)>> = Vec::new();
 
-    for l in lines {
-        let Ok(line) = l else {
-            return None;
+    for (i, l) in lines {
+        let line = l?;
+
+        let Some([name, left, right]) = parse_node(&line) else {
+            return Err(Error::parse(i + 1, 1, &line, "Expected node of the form AAA = (BBB, CCC)"));
         };
 
-        let Some(n) = parse_node(&line, &mut start_nodes) else {
-            return None;
+        debug!("{} = ({}, {})", name, left, right);
+
+        let ids = [&name, &left, &right].map(|name| graph.intern(name));
+        edges.resize(graph.len(), None);
+
+        if edges[ids[0]].replace((ids[1], ids[2])).is_some() {
+            return Err(Error::parse(i + 1, 1, &line, format!("Node {name} is defined twice")));
+        }
+    }
+
+    for (id, edge) in edges.into_iter().enumerate() {
+        let Some(edge) = edge else {
+            return Err(Error::invalid_state(format!("Node {} is used but never defined", graph.name(id))));
         };
 
-        println!("{:?}", n);
-        graph[n.id as usize] = Some(n);
+        graph.edges.push(edge);
     }
 
-    Some((graph, start_nodes))
+    Ok(graph)
 }
 
 
-fn parse_node(line: &str, start_nodes: &mut Vec<u64>) -> Option<Node> {
+/* The node's name followed by its left and right nodes */
+fn parse_node(line: &str) -> Option<[String; 3]> {
     let chars_vec = line.chars().collect::<Vec<char>>();
     let mut chars = chars_vec.iter().peekable();
 
-    let (id, is_start, is_end) = parse_id(&mut chars)?;
-    let (left_id, _, _) = parse_id(&mut chars)?;
-    let (right_id, _, _) = parse_id(&mut chars)?;
+    let name = parse_name(&mut chars)?;
+    let left = parse_name(&mut chars)?;
+    let right = parse_name(&mut chars)?;
 
-    if is_start {
-        start_nodes.push(id);
-    }
-
-    Some(
-        Node {
-            id,
-            left_id,
-            right_id,
-            is_end
-        }
-    )
+    parse_name(&mut chars).is_none().then_some([name, left, right])
 }
 
 
-fn parse_id<'a, I>(chars: &mut Peekable<I>) -> Option<(u64, bool, bool)> 
+/* Skips to the next run of letters and digits and reads it whole */
+fn parse_name<'a, I>(chars: &mut Peekable<I>) -> Option<String>
 where I: Iterator<Item = &'a char>  {
-    let mut output = None;
-    let mut output_val = 0;
-    let mut length = 0;
-    let mut is_end = false;
-    let mut is_start = false;
-
-
-    loop {
-        match chars.peek() {
-            ch @ Some('A'..='Z') => {
-                let ch = **ch.unwrap();
-                length += 1;
-
-                if length == 3 && ch == 'A' {
-                    is_start = true;
-                } else if length == 3 && ch =='Z' {
-                    is_end = true;
-                }
-
-                output_val = output_val * 26 + (
-                    ch as u64 - 'A' as u64
-                );
-
-                output = Some(output_val);
-                chars.next();
-            }
-            None => break,
-            _ => {
-                if output == None {
-                    chars.next();
-                } else {
-                    break;
-                }
-            }
+    let mut output = String::new();
+
+    while let Some(ch) = chars.peek() {
+        if ch.is_alphanumeric() {
+            output.push(**ch);
+        } else if !output.is_empty() {
+            break;
         }
+
+        chars.next();
+    }
+
+    (!output.is_empty()).then_some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigInt;
+    use super::{Day8, Arrivals, Walk, GHOSTS, parse_input, first_together, to_dot};
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE_1: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_2: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_3: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day8, EXAMPLE_1, P1), 2.into());
+    }
+
+    #[test]
+    fn part1_repeated_path_example() {
+        assert_eq!(solve_example(&Day8, EXAMPLE_2, P1), 6.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day8, EXAMPLE_3, P2), 6.into());
+    }
+
+    #[test]
+    fn names_of_any_length() {
+        let input = parse_input("R\n\nstart9 = (start9, Goal)\nGoal = (start9, start9)\n".as_bytes()).unwrap();
+        let start = input.graph.matching(|name| name == "start9")[0];
+
+        let walk = Walk { is_start: |name| name == "start9", is_end: |name| name == "Goal" };
+        assert_eq!(Arrivals::walk(&input, start, &walk).first(), Some(1));
+        assert_eq!(input.graph.name(start), "start9");
+
+        let Err(error) = parse_input("L\n\nAAA = (BBB, AAA)\n".as_bytes()) else {
+            panic!("BBB is never defined");
+        };
+        assert!(error.to_string().contains("BBB"));
     }
 
-    match output {
-        Some(n) => Some((n, is_start, is_end)),
-        None => None
+    fn ghosts(nodes: &str) -> Vec<Arrivals> {
+        let input = parse_input(format!("L\n\n{nodes}").as_bytes()).unwrap();
+
+        input.graph.matching(GHOSTS.is_start).into_iter()
+            .map(|start| Arrivals::walk(&input, start, &GHOSTS))
+            .collect()
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn ghosts_out_of_step() {
+        /* Arrives at 3, 5, 7, ... */
+        let odd = "1A = (1B, 1B)\n1B = (1C, 1C)\n1C = (1Z, 1Z)\n1Z = (1C, 1C)\n";
+        /* Arrives at 2, 5, 8, ..., so the first arrivals' lcm of 6 is wrong */
+        let thirds = "2A = (2B, 2B)\n2B = (2Z, 2Z)\n2Z = (2C, 2C)\n2C = (2D, 2D)\n2D = (2Z, 2Z)\n";
+        /* Arrives at 2, 4, 6, ... */
+        let even = "3A = (3B, 3B)\n3B = (3Z, 3Z)\n3Z = (3B, 3B)\n";
+        /* Arrives once at 1, then never again */
+        let once = "4A = (4Z, 4Z)\n4Z = (4B, 4B)\n4B = (4B, 4B)\n";
+
+        let walkers = ghosts(&format!("{odd}{thirds}"));
+        assert_eq!(walkers[1], Arrivals { once: vec![], loop_start: 2, loop_len: 3, repeating: vec![2] });
+        assert_eq!(first_together(&walkers), Some(5.into()));
+
+        assert_eq!(first_together(&ghosts(&format!("{odd}{even}"))), None);
+        assert_eq!(first_together(&ghosts(&format!("{thirds}{even}"))), Some(2.into()));
+        assert_eq!(first_together(&ghosts(once)), Some(1.into()));
+        assert_eq!(first_together(&ghosts(&format!("{once}{odd}"))), None);
+
+        /* Starting on an end node only counts once the walk comes back to it */
+        let input = parse_input("L\n\nA = (A, A)\n".as_bytes()).unwrap();
+        let walk = Walk { is_start: |name| name == "A", is_end: |name| name == "A" };
+        let walker = Arrivals::walk(&input, 0, &walk);
+
+        assert_eq!(walker, Arrivals { once: vec![], loop_start: 0, loop_len: 1, repeating: vec![1] });
+        assert_eq!(first_together(&[walker]), Some(1.into()));
+
+        /* Starts on its end node, then loops back to it every 3 steps */
+        let cycle = "3A = (3B, 3B)\n3B = (3C, 3C)\n3C = (3A, 3A)\n4A = (4B, 4B)\n4B = (4A, 4A)\n";
+        let input = parse_input(format!("L\n\n{cycle}").as_bytes()).unwrap();
+        let walk = Walk { is_start: |name| name.ends_with('A'), is_end: |name| name.ends_with('A') };
+        let walkers: Vec<Arrivals> = input.graph.matching(walk.is_start).into_iter()
+            .map(|start| Arrivals::walk(&input, start, &walk))
+            .collect();
+
+        assert_eq!(first_together(&walkers), Some(6.into()));
+
+        /* Prime loops whose lcm is well past u64 */
+        let primes = [1009_u64, 1013, 1019, 1021, 1031, 1033, 1039];
+        let walkers: Vec<Arrivals> = primes.into_iter()
+            .map(|p| Arrivals { once: vec![], loop_start: 1, loop_len: p, repeating: vec![p] })
+            .collect();
+
+        assert_eq!(first_together(&walkers), Some(primes.into_iter().map(BigInt::from).product()));
+    }
+
+    #[test]
+    fn draws_the_network() {
+        let input = parse_input(EXAMPLE_3.as_bytes()).unwrap();
+        let dot = to_dot(&input, &GHOSTS, Some("11A")).unwrap();
+
+        assert!(dot.starts_with("digraph network {\n    label=\"11A (red) loops every 2 steps from step 1"));
+        assert!(dot.contains("\"11A\" [shape=box, style=bold];"));
+        assert!(dot.contains("\"11Z\" [shape=doublecircle];"));
+        assert!(dot.contains("\"11B\" -> \"11Z\" [label=\"R\", color=red, penwidth=2];"));
+        assert!(dot.contains("\"XXX\" -> \"XXX\" [label=\"LR\"];"));
+        assert!(!dot.contains("22A"));
+
+        let dot = to_dot(&input, &GHOSTS, None).unwrap();
+        assert!(dot.contains("22A (blue) loops every 6 steps"));
+        assert!(to_dot(&input, &GHOSTS, Some("33A")).is_err());
+    }
+}
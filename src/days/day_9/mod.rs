@@ -1,119 +1,101 @@
-use crate::days::Part;
-use crate::days::{read_lines, parse_num};
+use crate::error::{Error, Result};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::days::parse_num;
+use crate::log::{info, debug};
 
+use std::io::BufRead;
+use num::{BigInt, Zero};
 
-pub fn run(file_name: &str, part: Part) -> Result<u64, &'static str> {
-    match part {
-        Part::P1 => part1(file_name),
-        Part::P2 => part2(file_name),
-    }
-}
-
-
-fn part1(file_name: &str) -> Result<u64, &'static str> {
-    let histories = parse_histories(file_name)?;
-    let mut output = 0;
+mod oasis;
 
-    for mut history in histories {
-        // println!("{:?}", history);
+use oasis::Sequence;
 
-        for head in 1..history.len() {
-            // println!(" - {}", head);
-            for curr in (1..(head + 1)).rev() {
-                let prev = curr - 1;
-                let diff = history[curr] - history[prev];
-
-                history[prev] = diff;
-
-                // println!("   - c{}, p{}, d{}", curr, prev, diff);
-                // println!("   - {:?}", history);
-            }
-        }
 
-        // println!("{:?}", history);
+pub struct Day9;
 
-        let mut local_value = 0;
+impl Solution for Day9 {
+    type Input = Vec<Sequence>;
 
-        for i in (0..history.len()).rev() {
-            local_value += history[i];
-        }
+    const DAY: u32 = 9;
+    const TITLE: &'static str = "Mirage Maintenance";
 
-        // println!("{}", local_value);
-        output += local_value;
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input> {
+        parse_histories(input)
     }
 
-    // println!("{}", output);
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(sum_predictions(input, |sequence| sequence.forward(1)).into())
+    }
 
-    Ok(output as u64)
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(sum_predictions(input, |sequence| sequence.backward(1)).into())
+    }
 }
 
 
-fn part2(file_name: &str) -> Result<u64, &'static str> {
-    let histories = parse_histories(file_name)?;
-    let mut output = 0;
+fn sum_predictions(histories: &[Sequence], predict: fn(&Sequence) -> BigInt) -> BigInt {
+    let mut output = BigInt::zero();
 
     for history in histories {
-        let mut history: Vec<i64> = history.into_iter().rev().collect();
-        println!("{:?}", history);
-
-        for head in 1..history.len() {
-            println!(" - {}", head);
-            for curr in (1..(head + 1)).rev() {
-                let prev = curr - 1;
-                let diff = history[curr] - history[prev];
-
-                history[prev] = diff;
-
-                println!("   - c{}, p{}, d{}", curr, prev, diff);
-                println!("   - {:?}", history);
-            }
-        }
+        let value = predict(history);
+        debug!("{} predicts {}", history, value);
 
-        println!("{:?}", history);
-
-        let mut local_value = 0;
-
-        for i in (0..history.len()).rev() {
-            local_value += history[i];
-        }
-
-        println!("{}", local_value);
-        output += local_value;
+        output += value;
     }
 
-    println!("{}", output);
+    info!("{}", output);
 
-    Ok(output as u64)
+    output
 }
 
 
-fn parse_histories(file_name: &str) -> Result<Vec<Vec<i64>>, &'static str> {
-    let Ok(lines) = read_lines(file_name) else {
-        return Err("Failed to read file");
-    };
+fn parse_histories<B: BufRead>(input: B) -> Result<Vec<Sequence>> {
+    let lines = input.lines();
 
     let mut output = Vec::new();
 
-    for l in lines {
-        let Ok(line) = l else {
-            return Err("Error in reading line from file");
-        };
+    for (i, l) in lines.enumerate() {
+        let line = l?;
+
+        if let Some(j) = line.find(|c: char| !c.is_ascii_digit() && c != '-' && c != ' ') {
+            return Err(Error::parse(i + 1, j + 1, &line, "Expected a space separated list of numbers"));
+        }
 
         let chars_vec = line.chars().collect::<Vec<char>>();
         let mut chars = chars_vec.iter().peekable();
         let mut numbers = Vec::new();
 
-        loop {
-            let Some(n) = parse_num(&mut chars, true) else {
-                break;
-            };
-
+        while let Some(n) = parse_num(&mut chars, true) {
             numbers.push(n);            
         }
 
-        output.push(numbers);
+        output.push(Sequence::new(&numbers));
     }
 
 
     Ok(output)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day9;
+    use crate::days::solve_example;
+    use crate::days::Part::{P1, P2};
+
+    const EXAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day9, EXAMPLE, P1), 114.into());
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day9, EXAMPLE, P2), 2.into());
+    }
+}
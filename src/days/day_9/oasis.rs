@@ -0,0 +1,162 @@
+use std::fmt;
+
+use num::{BigInt, BigRational, One, Signed, Zero};
+
+
+/*
+ * A history reading, kept as its difference table's leading diagonal:
+ * the first value, the first difference, the first difference of the
+ * differences and so on. That is Newton's form of the polynomial through
+ * the readings, so it can be evaluated at any step, even before the
+ * first reading or far past the last.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    leading: Vec<BigInt>,
+    len: usize
+}
+
+
+impl Sequence {
+    pub fn new(values: &[i64]) -> Sequence {
+        let mut row: Vec<BigInt> = values.iter().map(|&v| BigInt::from(v)).collect();
+        let mut leading = Vec::new();
+
+        /* Once a row is all zeros every row below it is too */
+        while row.iter().any(|v| !v.is_zero()) {
+            leading.push(row[0].clone());
+            row = row.windows(2).map(|pair| &pair[1] - &pair[0]).collect();
+        }
+
+        Sequence { leading, len: values.len() }
+    }
+
+    /* The value at a step, where the readings are steps 0 to len - 1 */
+    pub fn at(&self, step: i64) -> BigInt {
+        let step = BigInt::from(step);
+        let mut binomial = BigInt::one();
+        let mut value = BigInt::zero();
+
+        /* Steps through step choose k, which stays whole even for negative steps */
+        for (k, difference) in self.leading.iter().enumerate() {
+            value += difference * &binomial;
+            binomial = binomial * (&step - k) / (k + 1);
+        }
+
+        value
+    }
+
+    /* The value k steps after the last reading */
+    pub fn forward(&self, k: i64) -> BigInt {
+        self.at(self.len as i64 - 1 + k)
+    }
+
+    /* The value k steps before the first reading */
+    pub fn backward(&self, k: i64) -> BigInt {
+        self.at(-k)
+    }
+
+    /*
+     * The polynomial's coefficients in step, constant first. Expands each
+     * step choose k term, which is a product of (step - j) / (j + 1).
+     */
+    pub fn coefficients(&self) -> Vec<BigRational> {
+        let mut coefficients = vec![BigRational::zero(); self.leading.len()];
+        let mut term = vec![BigRational::one()];
+
+        for (k, difference) in self.leading.iter().enumerate() {
+            for (c, t) in coefficients.iter_mut().zip(&term) {
+                *c += t * BigRational::from(difference.clone());
+            }
+
+            /* Multiply the term by (step - k) / (k + 1) */
+            let scale = BigRational::from(BigInt::from(k + 1));
+            let mut next = vec![BigRational::zero(); term.len() + 1];
+
+            for (i, t) in term.iter().enumerate() {
+                next[i + 1] += t / &scale;
+                next[i] -= t * BigRational::from(BigInt::from(k)) / &scale;
+            }
+
+            term = next;
+        }
+
+        coefficients
+    }
+}
+
+
+/* Shows the fitted polynomial, highest power first, such as 1/2x^2 + 3/2x + 1 */
+impl fmt::Display for Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms: Vec<(usize, BigRational)> = self.coefficients().into_iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_zero())
+            .rev()
+            .collect();
+
+        if terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        for (i, (power, c)) in terms.iter().enumerate() {
+            let sign = match (i, c.is_negative()) {
+                (0, true) => "-",
+                (0, false) => "",
+                (_, true) => " - ",
+                (_, false) => " + ",
+            };
+            let c = c.abs();
+
+            write!(f, "{sign}")?;
+
+            if *power == 0 || !c.is_one() {
+                write!(f, "{c}")?;
+            }
+
+            match power {
+                0 => (),
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{power}")?,
+            };
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Sequence;
+    use num::{BigInt, BigRational};
+
+    #[test]
+    fn extrapolates_both_ways() {
+        let triangles = Sequence::new(&[1, 3, 6, 10, 15, 21]);
+
+        assert_eq!(triangles.forward(1), 28.into());
+        assert_eq!(triangles.forward(4), 55.into());
+        assert_eq!(triangles.backward(1), 0.into());
+        assert_eq!(triangles.backward(3), 1.into());
+        assert_eq!(triangles.to_string(), "1/2x^2 + 3/2x + 1");
+
+        let constant = Sequence::new(&[-4, -4, -4]);
+        assert_eq!(constant.backward(100), (-4).into());
+        assert_eq!(constant.to_string(), "-4");
+        assert_eq!(Sequence::new(&[0, 0]).to_string(), "0");
+    }
+
+    #[test]
+    fn stays_exact_beyond_i64() {
+        /* n^3 - 2n, evaluated far past where it fits in 64 bits */
+        let cubes = Sequence::new(&[0, -1, 4, 21, 56]);
+        let n = BigInt::from(10).pow(10);
+
+        assert_eq!(cubes.at(10_i64.pow(10)), n.pow(3) - 2 * &n);
+        assert_eq!(
+            cubes.coefficients(),
+            [0, -2, 0, 1].map(|c| BigRational::from(BigInt::from(c)))
+        );
+    }
+}
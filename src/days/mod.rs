@@ -16,20 +16,69 @@ pub mod day_15;
 pub mod day_16;
 pub mod day_17;
 
+use crate::error::Result;
+#[cfg(test)]
+use crate::answer::Answer;
+use crate::solution::DynSolution;
+
 use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
-use std::iter::Peekable;
+use std::io::{self, BufRead, BufReader};
+use std::iter::{Enumerate, Peekable};
+
+/*
+ * Every implemented day, in order. Days missing from here are reported
+ * as not implemented by the runner.
+ */
+pub fn registry() -> Vec<&'static dyn DynSolution> {
+    vec![
+        &day_1::Day1,
+        &day_2::Day2,
+        &day_3::Day3,
+        &day_4::Day4,
+        &day_5::Day5,
+        &day_6::Day6,
+        &day_7::Day7,
+        &day_8::Day8,
+        &day_9::Day9,
+        &day_10::Day10,
+        &day_11::Day11,
+        &day_12::Day12,
+        &day_13::Day13,
+        &day_14::Day14,
+        &day_15::Day15,
+        &day_16::Day16,
+        &day_17::Day17,
+    ]
+}
+
+
+pub fn find(day: u32) -> Option<&'static dyn DynSolution> {
+    registry().into_iter().find(|s| s.metadata().day == day)
+}
+
 
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum Part {
     P1,
     P2
 }
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/* Lines paired with their 0-based index, for reporting parse errors */
+pub type NumberedLines<B> = Enumerate<io::Lines<B>>;
+
+
+/* Opens a puzzle input, where a path of "-" reads from stdin */
+pub fn open_input(path: &str) -> io::Result<Box<dyn BufRead>> {
+    if path == "-" {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+
+    Ok(Box::new(BufReader::new(File::open(path)?)))
+}
+
+
+pub fn collect_lines<B: BufRead>(input: B) -> Result<Vec<String>> {
+    Ok(input.lines().collect::<io::Result<Vec<String>>>()?)
 }
 
 
@@ -54,7 +103,7 @@ where I: Iterator<Item = &'a char> {
             },
             None => break,
             _ => {
-                if skip_non_numeric && output == None {
+                if skip_non_numeric && output.is_none() {
                     chars.next();
                     continue
                 } else {
@@ -65,4 +114,18 @@ where I: Iterator<Item = &'a char> {
     }
 
     output.map(|v| v * sign)
-}
\ No newline at end of file
+}
+
+
+/*
+ * Runs an embedded puzzle example through the same parse and solve path
+ * the runner uses, panicking with the error if either step fails.
+ */
+#[cfg(test)]
+pub fn solve_example(solution: &dyn DynSolution, input: &str, part: Part) -> Answer {
+    let parsed = solution.parse(&mut input.as_bytes())
+        .unwrap_or_else(|e| panic!("failed to parse example:\n{e}"));
+
+    parsed.solve(part)
+        .unwrap_or_else(|e| panic!("failed to solve example {part:?}:\n{e}"))
+}
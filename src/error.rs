@@ -0,0 +1,80 @@
+use std::fmt;
+use std::io;
+
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse {
+        line: usize,
+        column: usize,
+        snippet: String,
+        reason: String
+    },
+    InvalidState(String),
+    Unsolvable(String)
+}
+
+
+impl Error {
+    /*
+     * line and column are 1-based, snippet is the full text of the
+     * offending line so the column can be pointed at when displayed.
+     */
+    pub fn parse<S>(line: usize, column: usize, snippet: &str, reason: S) -> Error
+    where S: Into<String> {
+        Error::Parse {
+            line,
+            column,
+            snippet: snippet.to_string(),
+            reason: reason.into()
+        }
+    }
+
+    pub fn invalid_state<S: Into<String>>(reason: S) -> Error {
+        Error::InvalidState(reason.into())
+    }
+
+    pub fn unsolvable<S: Into<String>>(reason: S) -> Error {
+        Error::Unsolvable(reason.into())
+    }
+}
+
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Parse { line, column, snippet, reason } => {
+                let gutter = line.to_string().len();
+
+                writeln!(f, "Parse error at line {line}, column {column}: {reason}")?;
+                writeln!(f, "{:gutter$} |", "")?;
+                writeln!(f, "{line} | {snippet}")?;
+                write!(f, "{:gutter$} | {:>column$}", "", "^")
+            },
+            Error::InvalidState(reason) => write!(f, "Invalid puzzle state: {reason}"),
+            Error::Unsolvable(reason) => write!(f, "Unsolvable: {reason}"),
+        }
+    }
+}
+
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
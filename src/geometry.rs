@@ -0,0 +1,228 @@
+use std::ops::{Add, Mul, Sub};
+
+use num::Integer;
+
+use self::Direction::*;
+
+
+/*
+ * Compass directions on a grid where y grows downwards, so North is
+ * (0, -1). Variants are listed clockwise from North, which the turning
+ * methods rely on.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest
+}
+
+/* A step between two points */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Vector {
+    pub dx: isize,
+    pub dy: isize
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize
+}
+
+
+impl Direction {
+    /* Clockwise from North */
+    pub const CARDINAL: [Direction; 4] = [North, East, South, West];
+
+    /* Clockwise from North, diagonals included */
+    pub const ALL: [Direction; 8] = [
+        North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest
+    ];
+
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.rotate(6)
+    }
+
+    pub fn invert(self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn offset(self) -> Vector {
+        let (dx, dy) = match self {
+            North => (0, -1),
+            NorthEast => (1, -1),
+            East => (1, 0),
+            SouthEast => (1, 1),
+            South => (0, 1),
+            SouthWest => (-1, 1),
+            West => (-1, 0),
+            NorthWest => (-1, -1),
+        };
+
+        Vector { dx, dy }
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            North => '^',
+            NorthEast => '↗',
+            East => '>',
+            SouthEast => '↘',
+            South => 'v',
+            SouthWest => '↙',
+            West => '<',
+            NorthWest => '↖',
+        }
+    }
+
+    /* Turns clockwise by eighths of a full turn */
+    fn rotate(self, eighths: usize) -> Direction {
+        Direction::ALL[(self as usize + eighths) % 8]
+    }
+}
+
+
+impl Vector {
+    pub fn new(dx: isize, dy: isize) -> Vector {
+        Vector { dx, dy }
+    }
+
+    pub fn manhattan(self) -> usize {
+        self.dx.unsigned_abs() + self.dy.unsigned_abs()
+    }
+
+    /* Steps needed when diagonal moves are allowed */
+    pub fn chebyshev(self) -> usize {
+        self.dx.unsigned_abs().max(self.dy.unsigned_abs())
+    }
+}
+
+
+impl Point {
+    pub fn new(x: isize, y: isize) -> Point {
+        Point { x, y }
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        (other - self).manhattan()
+    }
+}
+
+
+/*
+ * Twice the area of the polygon through the vertices in order, by the
+ * shoelace formula. Doubled so it stays whole, and the last vertex joins
+ * back up to the first.
+ */
+pub fn double_area(vertices: &[Point]) -> usize {
+    let edges = vertices.iter().zip(vertices.iter().cycle().skip(1));
+
+    edges.map(|(a, b)| a.x * b.y - b.x * a.y).sum::<isize>().unsigned_abs()
+}
+
+
+/*
+ * The number of whole points strictly inside a polygon whose vertices
+ * are whole points, by Pick's theorem: area = inside + boundary / 2 - 1.
+ * An edge passes through one boundary point per step of the gcd of its
+ * length across and down.
+ */
+pub fn interior_points(vertices: &[Point]) -> usize {
+    let edges = vertices.iter().zip(vertices.iter().cycle().skip(1));
+    let boundary: usize = edges.map(|(a, b)| (b.x - a.x).gcd(&(b.y - a.y)).unsigned_abs()).sum();
+
+    (double_area(vertices) + 2).saturating_sub(boundary) / 2
+}
+
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Point {
+        Point::new(x as isize, y as isize)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, v: Vector) -> Point {
+        Point::new(self.x + v.dx, self.y + v.dy)
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<isize> for Vector {
+    type Output = Vector;
+
+    fn mul(self, n: isize) -> Vector {
+        Vector::new(self.dx * n, self.dy * n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_and_inverts() {
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(West.turn_right(), North);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(NorthEast.turn_left(), NorthWest);
+        assert_eq!(SouthWest.invert(), NorthEast);
+
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.offset() * -1, dir.invert().offset());
+        }
+    }
+
+    #[test]
+    fn lists_cardinals_among_all() {
+        assert!(Direction::CARDINAL.iter().all(|d| d.offset().manhattan() == 1));
+        assert_eq!(Direction::ALL.iter().filter(|d| d.offset().manhattan() == 2).count(), 4);
+    }
+
+    #[test]
+    fn measures_manhattan_distance() {
+        let a = Point::new(1, 6);
+        let b = a + South.offset() * 5 + East.offset() * 4;
+
+        assert_eq!(b, Point::new(5, 11));
+        assert_eq!(a.manhattan(b), 9);
+        assert_eq!(b.manhattan(a), 9);
+        assert_eq!((b - a).chebyshev(), 5);
+    }
+
+    #[test]
+    fn counts_points_inside_polygons() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)].map(|(x, y)| Point::new(x, y));
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(interior_points(&square), 9);
+
+        /* Winding the other way round, with a slanted edge */
+        let triangle = [(0, 0), (0, 4), (4, 0)].map(|(x, y)| Point::new(x, y));
+        assert_eq!(double_area(&triangle), 16);
+        assert_eq!(interior_points(&triangle), 3);
+
+        let line = [(0, 0), (3, 0)].map(|(x, y)| Point::new(x, y));
+        assert_eq!(interior_points(&line), 0);
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+use std::io::BufRead;
+use std::ops::{Index, IndexMut};
+
+use crate::error::{Error, Result};
+use crate::geometry::{Direction, Vector};
+
+
+/*
+ * A rectangular grid stored row by row. Positions are (x, y) with x the
+ * column and y the row, counted from the top left corner.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "grid cells do not fill {width}x{height}");
+
+        Grid { cells, width, height }
+    }
+
+    pub fn parse<B, F>(input: B, f: F) -> Result<Grid<T>>
+    where B: BufRead, F: FnMut(char) -> Option<T> {
+        let lines = input.lines().collect::<std::io::Result<Vec<String>>>()?;
+
+        Grid::from_lines(lines.iter().enumerate(), f)
+    }
+
+    /*
+     * Builds a grid from lines paired with their 0-based index in the
+     * input, so errors point at the right place even when the grid is
+     * only one block of a larger file. Each character is converted by
+     * f, where None marks it as invalid.
+     */
+    pub fn from_lines<I, S, F>(lines: I, mut f: F) -> Result<Grid<T>>
+    where I: IntoIterator<Item = (usize, S)>, S: AsRef<str>, F: FnMut(char) -> Option<T> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        let mut first_line = 0;
+
+        for (i, line) in lines {
+            let line = line.as_ref();
+
+            if height == 0 {
+                first_line = i;
+            }
+
+            for (j, ch) in line.chars().enumerate() {
+                let Some(cell) = f(ch) else {
+                    return Err(Error::parse(i + 1, j + 1, line, format!("Unexpected character '{ch}'")));
+                };
+
+                cells.push(cell);
+            }
+
+            let row_width = line.chars().count();
+
+            match width {
+                None => width = Some(row_width),
+                Some(w) if w != row_width => return Err(Error::parse(
+                    i + 1, row_width.min(w) + 1, line,
+                    format!("Expected a row of width {w}, found {row_width}")
+                )),
+                Some(_) => ()
+            };
+
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid { cells, width, height }),
+            _ => Err(Error::parse(first_line + 1, 1, "", "Expected a grid")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index_of(x, y).map(|i| &mut self.cells[i])
+    }
+
+    pub fn swap(&mut self, a: (usize, usize), b: (usize, usize)) {
+        let a = self.index_of(a.0, a.1).expect("position outside grid");
+        let b = self.index_of(b.0, b.1).expect("position outside grid");
+
+        self.cells.swap(a, b);
+    }
+
+    /* The position v away from x, y, if it is still inside the grid */
+    pub fn offset(&self, x: usize, y: usize, v: Vector) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(v.dx)?;
+        let y = y.checked_add_signed(v.dy)?;
+
+        self.index_of(x, y).map(|_| (x, y))
+    }
+
+    /* The neighbour one step in dir, if it is still inside the grid */
+    pub fn step(&self, x: usize, y: usize, dir: Direction) -> Option<(usize, usize)> {
+        self.offset(x, y, dir.offset())
+    }
+
+    /* Orthogonal neighbours, clockwise from north */
+    #[allow(dead_code)] /* The 4-way half of the neighbour API; the ported days all need the heading too */
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::CARDINAL.into_iter().filter_map(move |dir| self.step(x, y, dir))
+    }
+
+    /* Orthogonal and diagonal neighbours, clockwise from north */
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL.into_iter().filter_map(move |dir| self.step(x, y, dir))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /* A grid of width 0 has no cells, so it has no rows either */
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            None
+        } else {
+            Some((y * self.width) + x)
+        }
+    }
+}
+
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /* Swaps rows and columns, so column x becomes row x */
+    pub fn transpose(&self) -> Grid<T> {
+        self.rebuild(self.height, self.width, |x, y| (y, x))
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        self.rebuild(self.height, self.width, |x, y| (y, self.height - 1 - x))
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Grid<T> {
+        self.rebuild(self.height, self.width, |x, y| (self.width - 1 - y, x))
+    }
+
+    /* A width by height grid where each cell is copied from source(x, y) */
+    fn rebuild<F>(&self, width: usize, height: usize, source: F) -> Grid<T>
+    where F: Fn(usize, usize) -> (usize, usize) {
+        let mut cells = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = source(x, y);
+                cells.push(self[(sx, sy)].clone());
+            }
+        }
+
+        Grid::new(width, height, cells)
+    }
+}
+
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).expect("position outside grid")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        self.get_mut(x, y).expect("position outside grid")
+    }
+}
+
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Grid<char> {
+        Grid::parse(text.as_bytes(), Some).unwrap()
+    }
+
+    #[test]
+    fn parses_and_displays() {
+        let grid = chars("ab\ncd\nef\n");
+
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.to_string(), "ab\ncd\nef\n");
+    }
+
+    #[test]
+    fn reports_bad_cells_and_ragged_rows() {
+        let digits = |ch: char| ch.to_digit(10);
+
+        let Err(Error::Parse { line, column, .. }) = Grid::parse("12\n3x\n".as_bytes(), digits) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((line, column), (2, 2));
+
+        let Err(Error::Parse { line, .. }) = Grid::parse("12\n345\n".as_bytes(), digits) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(line, 2);
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = chars("abc\ndef\nghi\n");
+
+        assert_eq!(grid.neighbors4(0, 0).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors8(1, 1).count(), 8);
+        assert_eq!(grid.neighbors8(2, 2).collect::<Vec<_>>(), [(2, 1), (1, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn iterates_rows_and_columns() {
+        let grid = chars("abc\ndef\n");
+
+        assert_eq!(grid.rows().nth(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.columns().map(|c| c.collect::<String>()).collect::<Vec<_>>(), ["ad", "be", "cf"]);
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &'e')));
+        assert_eq!(Grid::<char>::new(0, 3, vec![]).rows().count(), 0);
+    }
+
+    #[test]
+    fn transposes_and_rotates_non_square_grids() {
+        let grid = chars("abc\ndef\n");
+
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.rotate_clockwise().rotate_counter_clockwise(), grid);
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+
+
+/* The half open range [start, end) */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64
+}
+
+/*
+ * A set of numbers stored as sorted intervals. Intervals never overlap or
+ * touch, so each set has exactly one representation and sets can be
+ * compared directly.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>
+}
+
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Interval {
+        Interval { start, end }
+    }
+
+    pub fn from_len(start: i64, len: i64) -> Interval {
+        Interval::new(start, start + len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, n: i64) -> bool {
+        self.start <= n && n < self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let overlap = Interval::new(self.start.max(other.start), self.end.min(other.end));
+
+        (!overlap.is_empty()).then_some(overlap)
+    }
+
+    pub fn shift(&self, delta: i64) -> Interval {
+        Interval::new(self.start + delta, self.end + delta)
+    }
+}
+
+
+impl IntervalSet {
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+
+    /* The smallest number in the set */
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (a, b) = (&self.intervals, &other.intervals);
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        /* Both lists are sorted, so step past whichever interval ends first */
+        while i < a.len() && j < b.len() {
+            if let Some(overlap) = a[i].intersection(&b[j]) {
+                intervals.push(overlap);
+            }
+
+            if a[i].end < b[j].end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+
+        for interval in &self.intervals {
+            let mut start = interval.start;
+
+            for cut in other.iter().filter(|cut| cut.intersection(interval).is_some()) {
+                if cut.start > start {
+                    intervals.push(Interval::new(start, cut.start));
+                }
+                start = start.max(cut.end);
+            }
+
+            if start < interval.end {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+
+        IntervalSet { intervals }
+    }
+
+    pub fn shift(&self, delta: i64) -> IntervalSet {
+        IntervalSet {
+            intervals: self.iter().map(|interval| interval.shift(delta)).collect()
+        }
+    }
+}
+
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> IntervalSet {
+        IntervalSet::from_iter([interval])
+    }
+}
+
+/* Sorts the intervals and merges any that overlap or touch */
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> IntervalSet {
+        let mut sorted: Vec<Interval> = iter.into_iter().filter(|i| !i.is_empty()).collect();
+        let mut intervals: Vec<Interval> = Vec::with_capacity(sorted.len());
+
+        sorted.sort();
+
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => intervals.push(interval),
+            }
+        }
+
+        IntervalSet { intervals }
+    }
+}
+
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let intervals: Vec<String> = self.iter().map(|interval| interval.to_string()).collect();
+
+        write!(f, "{}", intervals.join(" "))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    #[test]
+    fn merges_overlapping_and_touching_intervals() {
+        assert_eq!(set(&[(5, 8), (0, 2), (2, 3), (7, 10), (20, 20)]), set(&[(0, 3), (5, 10)]));
+        assert_eq!(set(&[(0, 3)]).union(&set(&[(10, 12), (3, 4)])), set(&[(0, 4), (10, 12)]));
+        assert_eq!(set(&[(4, 9)]).min(), Some(4));
+        assert_eq!(IntervalSet::default().min(), None);
+    }
+
+    #[test]
+    fn intersects_and_subtracts() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 28)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20), (30, 40)]));
+        assert_eq!(a.difference(&a), IntervalSet::default());
+    }
+
+    #[test]
+    fn shifts_every_interval() {
+        assert_eq!(set(&[(0, 2), (5, 6)]).shift(-3), set(&[(-3, -1), (2, 3)]));
+        assert_eq!(Interval::from_len(7, 3).to_string(), "[7, 10)");
+    }
+}
@@ -0,0 +1,86 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+
+/*
+ * Diagnostic output for the solutions. Messages go to stderr so that
+ * stdout only ever holds the answers, and nothing is printed unless the
+ * level has been raised from the command line.
+ *
+ *   info  - a line or two per puzzle item (each hand, pattern, history)
+ *   debug - dumps of parsed inputs and intermediate grids
+ *   trace - every step of the inner loops
+ */
+#[derive(Debug, Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
+pub enum Level {
+    Off,
+    Info,
+    Debug,
+    Trace
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Off as u8);
+
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Level, String> {
+        match s {
+            "off" => Ok(Level::Off),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("Invalid log level '{s}', expected off, info, debug or trace")),
+        }
+    }
+}
+
+
+/* Arguments are only evaluated when the level is enabled */
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Info, $($arg)*) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Debug, $($arg)*) };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Trace, $($arg)*) };
+}
+
+pub(crate) use {log, info, debug, trace};
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_levels_in_order() {
+        let levels = ["off", "info", "debug", "trace"].map(|s| s.parse::<Level>().unwrap());
+
+        assert_eq!(levels, [Level::Off, Level::Info, Level::Debug, Level::Trace]);
+        assert!(levels.windows(2).all(|w| w[0] < w[1]));
+        assert!("verbose".parse::<Level>().is_err());
+    }
+}
@@ -1,52 +1,380 @@
+mod answer;
+mod answers;
+mod cli;
 mod days;
+mod error;
+mod geometry;
+mod grid;
+mod interval;
+mod log;
+mod report;
+mod search;
+mod solution;
+mod timing;
 
-use days::*;
+use std::env;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::ExitCode;
+
+use answers::Answers;
+use error::{Error, Result};
+use report::{Row, Status};
+use solution::{DynSolution, ParsedInput};
+use timing::Summary;
+use cli::{Command, DaySelection};
+use days::Part;
 use days::Part::{P1, P2};
 
 
-fn main() {
-    let day = 17;
-
-    let function = match day {
-        1 => |p| day_1::run("inputs/day_1.txt", p),
-        2 => |p| day_2::run("inputs/day_2.txt", p),
-        3 => |p| day_3::run("inputs/day_3.txt", p),
-        4 => |p| day_4::run("inputs/day_4.txt", p),
-        5 => |p| day_5::run("inputs/day_5.txt", p),
-        6 => |p| day_6::run("inputs/day_6.txt", p),
-        7 => |p| day_7::run("inputs/day_7.txt", p),
-        8 => |p| day_8::run("inputs/day_8.txt", p),
-        9 => |p| day_9::run("inputs/day_9.txt", p),
-        10 => |p| day_10::run("inputs/day_10.txt", p),
-        11 => |p| day_11::run("inputs/day_11.txt", p),
-        12 => |p| day_12::run("inputs/day_12.txt", p),
-        13 => |p| day_13::run("inputs/day_13.txt", p),
-        14 => |p| day_14::run("inputs/day_14.txt", p),
-        15 => |p| day_15::run("inputs/day_15.txt", p),
-        16 => |p| day_16::run("inputs/day_16.txt", p),
-        17 => |p| day_17::run("inputs/day_17.txt", p),
-        18 => |_p| todo!(),
-        19 => |_p| todo!(),
-        20 => |_p| todo!(),
-        21 => |_p| todo!(),
-        22 => |_p| todo!(),
-        23 => |_p| todo!(),
-        24 => |_p| todo!(),
-        25 => |_p| todo!(),
-        _ => |_| Result::Err("Invalid Day"),
-    };
-
-    let result = function(P1)
-        .map_err(|e| format!("Failed P1: {e}"))
-        .and_then(|r| {
-            println!("P1 Result: {}", r);
-            return function(P2)
-                .map_err(|e| format!("Failed P2: {e}"))
-                .map(|r| println!("P2 Results: {}", r));
-        });
-
-    match result {
-        Ok(_) => (),
-        Err(reason) => println!("{}", reason)
-    };
-}
\ No newline at end of file
+fn main() -> ExitCode {
+    let args = match cli::parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(reason) => {
+            eprintln!("{reason}\n\n{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    log::set_level(args.log_level);
+
+    match args.command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        },
+        Command::Run { days, part, input } => run(days, part, input),
+        Command::Bench { days, part, input, runs } => bench(days, part, input, runs),
+        Command::Verify { days, answers } => verify(days, answers),
+        Command::Explain { day, query, part, input } => explain(day, &query, part, input),
+    }
+}
+
+
+fn parse_file<'a>(solution: &'a dyn DynSolution, file_name: &str) -> Result<Box<dyn ParsedInput + 'a>> {
+    let mut reader = days::open_input(file_name)?;
+
+    solution.parse(&mut reader)
+}
+
+
+fn run(days: DaySelection, part: Option<Part>, input: Option<String>) -> ExitCode {
+    let parts = match part {
+        Some(p) => vec![p],
+        None => vec![P1, P2],
+    };
+
+    let (selected, run_all) = match days {
+        DaySelection::Day(day) => (vec![day], false),
+        DaySelection::All => ((1..=25).collect(), true),
+    };
+
+    /* Only used to fill in the summary, so a missing file just means unverified */
+    let answers = match Answers::load("answers.toml") {
+        Ok(answers) => answers,
+        Err(Error::Io(e)) if e.kind() == io::ErrorKind::NotFound => Answers::default(),
+        Err(reason) => {
+            eprintln!("Ignoring answers.toml: {reason}");
+            Answers::default()
+        }
+    };
+    let mut rows = Vec::new();
+    let mut failed = false;
+
+    for day in selected {
+        let Some(solution) = days::find(day) else {
+            if run_all {
+                rows.push(Row { day, part: None, answer: None, time: None, status: Status::NotImplemented });
+            } else {
+                eprintln!("Day {day} is not implemented");
+                failed = true;
+            }
+            continue;
+        };
+
+        let metadata = solution.metadata();
+        println!("--- {} Day {day}: {} ---", metadata.year, metadata.title);
+
+        let file_name = input.clone()
+            .unwrap_or_else(|| format!("inputs/day_{day}.txt"));
+
+        let (parsed, parse_time) = timing::time(|| parse_file(solution, &file_name));
+
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(Error::Io(e)) if run_all && e.kind() == io::ErrorKind::NotFound => {
+                println!("Day {day} No input at {file_name}");
+                rows.push(Row { day, part: None, answer: None, time: None, status: Status::NoInput });
+                continue;
+            },
+            Err(reason) => {
+                eprintln!("Day {day} Failed to parse input: {reason}");
+                rows.push(Row { day, part: None, answer: None, time: None, status: Status::Failed });
+                failed = true;
+                continue;
+            }
+        };
+
+        println!("Day {day} Parsed input in {parse_time:.2?}");
+
+        for part in &parts {
+            let (result, time) = timing::time(|| parsed.solve(*part));
+
+            match result {
+                Ok(r) => {
+                    println!("Day {day} {part:?} Result: {r} ({time:.2?})");
+
+                    let status = Status::check(&answers, day, *part, &r);
+                    rows.push(Row { day, part: Some(*part), answer: Some(r), time: Some(time), status });
+                },
+                Err(reason) => {
+                    eprintln!("Day {day} {part:?} Failed: {reason}");
+                    rows.push(Row { day, part: Some(*part), answer: None, time: Some(time), status: Status::Failed });
+                    failed = true;
+                }
+            };
+        }
+    }
+
+    if run_all {
+        println!();
+        report::print_summary(&rows);
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+
+/*
+ * Repeats parsing and solving each selected day, then reports the spread
+ * of timings. The input is read into memory once up front so the parse
+ * timings do not include any I/O.
+ */
+fn bench(days: DaySelection, part: Option<Part>, input: Option<String>, runs: usize) -> ExitCode {
+    let parts = match part {
+        Some(p) => vec![p],
+        None => vec![P1, P2],
+    };
+
+    let (selected, run_all) = match days {
+        DaySelection::Day(day) => (vec![day], false),
+        DaySelection::All => ((1..=25).collect(), true),
+    };
+
+    let mut failed = false;
+
+    for day in selected {
+        let Some(solution) = days::find(day) else {
+            if !run_all {
+                eprintln!("Day {day} is not implemented");
+                failed = true;
+            }
+            continue;
+        };
+
+        let metadata = solution.metadata();
+        println!("--- {} Day {day}: {} ---", metadata.year, metadata.title);
+
+        let file_name = input.clone()
+            .unwrap_or_else(|| format!("inputs/day_{day}.txt"));
+
+        let mut text = String::new();
+
+        match days::open_input(&file_name).and_then(|mut reader| reader.read_to_string(&mut text)) {
+            Ok(_) => (),
+            Err(e) if run_all && e.kind() == io::ErrorKind::NotFound => {
+                println!("Day {day} No input at {file_name}");
+                continue;
+            },
+            Err(reason) => {
+                eprintln!("Day {day} Failed to read {file_name}: {reason}");
+                failed = true;
+                continue;
+            }
+        };
+
+        match bench_day(solution, &text, &parts, runs) {
+            Ok(summaries) => {
+                for (stage, summary) in summaries {
+                    println!(
+                        "Day {day} {stage:<5} min {:>10.2?}  median {:>10.2?}  max {:>10.2?}",
+                        summary.min, summary.median, summary.max
+                    );
+                }
+            },
+            Err(reason) => {
+                eprintln!("Day {day} Failed: {reason}");
+                failed = true;
+            }
+        };
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+
+fn bench_day(
+    solution: &dyn DynSolution, text: &str, parts: &[Part], runs: usize
+) -> Result<Vec<(String, Summary)>> {
+    let mut parse_times = Vec::new();
+    let mut part_times = vec![Vec::new(); parts.len()];
+
+    for _ in 0..runs {
+        let (parsed, time) = timing::time(|| solution.parse(&mut text.as_bytes()));
+        let parsed = parsed?;
+        parse_times.push(time);
+
+        for (i, part) in parts.iter().enumerate() {
+            let (result, time) = timing::time(|| parsed.solve(*part));
+            result?;
+            part_times[i].push(time);
+        }
+    }
+
+    let mut summaries = Vec::new();
+    summaries.extend(Summary::from_samples(&parse_times).map(|s| ("Parse".to_string(), s)));
+
+    for (part, times) in parts.iter().zip(&part_times) {
+        summaries.extend(Summary::from_samples(times).map(|s| (format!("{part:?}"), s)));
+    }
+
+    Ok(summaries)
+}
+
+
+/*
+ * Runs each selected day against its default input and compares every
+ * part with the recorded answer. Days without an input file are skipped,
+ * so a fresh checkout only verifies what it can.
+ */
+fn verify(days: DaySelection, answers: Option<String>) -> ExitCode {
+    let answers_file = answers.unwrap_or_else(|| "answers.toml".to_string());
+
+    let answers = match Answers::load(&answers_file) {
+        Ok(answers) => answers,
+        Err(reason) => {
+            eprintln!("Failed to read answers from {answers_file}: {reason}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (selected, run_all) = match days {
+        DaySelection::Day(day) => (vec![day], false),
+        DaySelection::All => ((1..=25).collect(), true),
+    };
+
+    let (mut passed, mut mismatched, mut failed, mut skipped) = (0, 0, 0, 0);
+
+    for day in selected {
+        /* Checking all days only covers the implemented ones */
+        let Some(solution) = days::find(day) else {
+            if !run_all {
+                println!("Day {day} FAIL: not implemented");
+                failed += 2;
+            }
+            continue;
+        };
+
+        let file_name = format!("inputs/day_{day}.txt");
+
+        if !Path::new(&file_name).exists() {
+            println!("Day {day} SKIPPED: no input at {file_name}");
+            skipped += 2;
+            continue;
+        }
+
+        let parsed = match parse_file(solution, &file_name) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                println!("Day {day} FAIL: could not parse input: {reason}");
+                failed += 2;
+                continue;
+            }
+        };
+
+        for part in [P1, P2] {
+            let actual = match parsed.solve(part) {
+                Ok(actual) => actual,
+                Err(reason) => {
+                    println!("Day {day} {part:?} FAIL: {reason}");
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            match answers.get(day, part) {
+                Some(expected) if *expected == actual => {
+                    println!("Day {day} {part:?} PASS: {actual}");
+                    passed += 1;
+                },
+                Some(expected) => {
+                    println!("Day {day} {part:?} MISMATCH: expected {expected}, got {actual}");
+                    mismatched += 1;
+                },
+                None => {
+                    println!("Day {day} {part:?} SKIPPED: no recorded answer, got {actual}");
+                    skipped += 1;
+                }
+            };
+        }
+    }
+
+    println!("\n{passed} passed, {mismatched} mismatched, {failed} failed, {skipped} skipped");
+
+    if mismatched + failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+
+fn explain(day: u32, query: &str, part: Option<Part>, input: Option<String>) -> ExitCode {
+    let parts = match part {
+        Some(p) => vec![p],
+        None => vec![P1, P2],
+    };
+
+    let Some(solution) = days::find(day) else {
+        eprintln!("Day {day} is not implemented");
+        return ExitCode::FAILURE;
+    };
+
+    let file_name = input.unwrap_or_else(|| format!("inputs/day_{day}.txt"));
+
+    let parsed = match parse_file(solution, &file_name) {
+        Ok(parsed) => parsed,
+        Err(reason) => {
+            eprintln!("Day {day} Failed to parse input: {reason}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for part in parts {
+        match parsed.explain(part, query) {
+            /* The label goes to stderr so explanations like day 8's map can be redirected to a file */
+            Some(Ok(text)) => {
+                eprintln!("Day {day} {part:?}");
+                println!("{text}");
+            },
+            Some(Err(reason)) => {
+                eprintln!("Day {day} {part:?} Failed: {reason}");
+                return ExitCode::FAILURE;
+            },
+            None => {
+                eprintln!("Day {day} has nothing to explain");
+                return ExitCode::FAILURE;
+            }
+        };
+    }
+
+    ExitCode::SUCCESS
+}
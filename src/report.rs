@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use colored::{ColoredString, Colorize};
+
+use crate::answer::Answer;
+use crate::answers::Answers;
+use crate::days::Part;
+
+
+#[derive(Debug)]
+pub enum Status {
+    Verified,
+    Mismatch(Answer),
+    Unverified,
+    Failed,
+    NoInput,
+    NotImplemented
+}
+
+#[derive(Debug)]
+pub struct Row {
+    pub day: u32,
+    pub part: Option<Part>,
+    pub answer: Option<Answer>,
+    pub time: Option<Duration>,
+    pub status: Status
+}
+
+
+impl Status {
+    pub fn check(answers: &Answers, day: u32, part: Part, actual: &Answer) -> Status {
+        match answers.get(day, part) {
+            Some(expected) if expected == actual => Status::Verified,
+            Some(expected) => Status::Mismatch(expected.clone()),
+            None => Status::Unverified,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Status::Verified => "verified".to_string(),
+            Status::Mismatch(expected) => format!("mismatch, expected {expected}"),
+            Status::Unverified => "unverified".to_string(),
+            Status::Failed => "failed".to_string(),
+            Status::NoInput => "no input".to_string(),
+            Status::NotImplemented => "not implemented".to_string(),
+        }
+    }
+
+    fn paint(&self) -> ColoredString {
+        let text = self.describe();
+
+        match self {
+            Status::Verified => text.green(),
+            Status::Mismatch(_) | Status::Failed => text.red().bold(),
+            Status::Unverified | Status::NoInput => text.yellow(),
+            Status::NotImplemented => text.dimmed(),
+        }
+    }
+}
+
+
+pub fn print_summary(rows: &[Row]) {
+    let cells: Vec<[String; 4]> = rows.iter()
+        .map(|row| [
+            row.day.to_string(),
+            row.part.map_or("-".to_string(), |p| format!("{p:?}")),
+            row.answer.as_ref().map_or("-".to_string(), |a| a.to_string()),
+            row.time.map_or("-".to_string(), |t| format!("{t:.2?}")),
+        ])
+        .collect();
+
+    let headers = ["Day", "Part", "Answer", "Time", "Status"];
+    let mut widths = headers.map(str::len);
+
+    for (row, cell) in rows.iter().zip(&cells) {
+        for (i, text) in cell.iter().enumerate() {
+            widths[i] = widths[i].max(text.len());
+        }
+        widths[4] = widths[4].max(row.status.describe().len());
+    }
+
+    let header = headers.iter().zip(widths)
+        .map(|(h, w)| format!("{h:<w$}"))
+        .collect::<Vec<String>>()
+        .join("  ");
+
+    println!("{}", header.bold());
+    println!("{}", "-".repeat(header.len()));
+
+    for (row, cell) in rows.iter().zip(&cells) {
+        println!(
+            "{:>w0$}  {:<w1$}  {:>w2$}  {:>w3$}  {}",
+            cell[0], cell[1], cell[2], cell[3], row.status.paint(),
+            w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+
+
+/*
+ * Shortest path searches over any state type. Each search starts from one
+ * state, expands states through a neighbors function yielding
+ * (next state, step cost) and stops at the first state accepted by
+ * is_goal. The result is the total cost and every state on the path,
+ * start and goal included, or None when no goal can be reached.
+ *
+ * Costs only need to add up and compare, with Default as zero.
+ */
+
+
+/*
+ * A* search. The heuristic must never overestimate the remaining cost to
+ * a goal, otherwise the returned path may not be the cheapest.
+ */
+pub fn astar<S, C, N, I, H, G>(start: S, mut neighbors: N, mut heuristic: H, mut is_goal: G) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool
+{
+    /* States are numbered in the order they are found, the queue holds their numbers */
+    let mut states = vec![start.clone()];
+    let mut numbers = HashMap::from([(start, 0)]);
+    let mut costs = vec![C::default()];
+    let mut parents = vec![None];
+    let mut queue = BinaryHeap::new();
+
+    queue.push(Reverse((heuristic(&states[0]), C::default(), 0)));
+
+    while let Some(Reverse((_, cost, i))) = queue.pop() {
+        if cost > costs[i] {
+            continue; // A cheaper route was queued after this one
+        }
+
+        if is_goal(&states[i]) {
+            return Some((cost, path_to(&states, &parents, i)));
+        }
+
+        for (next, step) in neighbors(&states[i]) {
+            let next_cost = cost + step;
+
+            let j = match numbers.get(&next) {
+                Some(&j) if costs[j] <= next_cost => continue,
+                Some(&j) => {
+                    costs[j] = next_cost;
+                    parents[j] = Some(i);
+                    j
+                },
+                None => {
+                    let j = states.len();
+                    numbers.insert(next.clone(), j);
+                    states.push(next);
+                    costs.push(next_cost);
+                    parents.push(Some(i));
+                    j
+                }
+            };
+
+            queue.push(Reverse((next_cost + heuristic(&states[j]), next_cost, j)));
+        }
+    }
+
+    None
+}
+
+
+pub fn dijkstra<S, C, N, I, G>(start: S, neighbors: N, is_goal: G) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool
+{
+    astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+
+/* Every step costs one, so the cost is the number of steps taken */
+pub fn bfs<S, N, I, G>(start: S, mut neighbors: N, is_goal: G) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool
+{
+    dijkstra(start, |state| neighbors(state).into_iter().map(|next| (next, 1)), is_goal)
+}
+
+
+fn path_to<S: Clone>(states: &[S], parents: &[Option<usize>], end: usize) -> Vec<S> {
+    let mut path = vec![states[end].clone()];
+    let mut i = end;
+
+    while let Some(parent) = parents[i] {
+        path.push(states[parent].clone());
+        i = parent;
+    }
+
+    path.reverse();
+    path
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* a -> b costs 7 directly, but 2 + 2 + 1 via c and d */
+    fn edges(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 7), ('c', 2)],
+            'c' => vec![('d', 2), ('a', 2)],
+            'd' => vec![('b', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn dijkstra_finds_the_cheapest_path() {
+        assert_eq!(dijkstra('a', edges, |n| *n == 'b'), Some((5, vec!['a', 'c', 'd', 'b'])));
+        assert_eq!(dijkstra('a', edges, |n| *n == 'a'), Some((0, vec!['a'])));
+        assert_eq!(dijkstra('b', edges, |n| *n == 'a'), None);
+    }
+
+    #[test]
+    fn astar_and_bfs_on_an_open_grid() {
+        let moves = |&(x, y): &(i32, i32)| {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+                .into_iter()
+                .filter(|&(x, y)| (0..5).contains(&x) && (0..5).contains(&y) && (x, y) != (2, 1))
+        };
+        let goal = (4, 3);
+        let distance = |&(x, y): &(i32, i32)| (goal.0 - x).abs() + (goal.1 - y).abs();
+
+        let (cost, path) = astar((0, 0), |s| moves(s).map(|n| (n, 1)), distance, |s| *s == goal).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.len(), 8);
+        assert!(path.windows(2).all(|w| distance(&w[0]) - distance(&w[1]) == 1));
+
+        let (steps, path) = bfs((0, 0), moves, |s| *s == goal).unwrap();
+        assert_eq!((steps, path.first(), path.last()), (7, Some(&(0, 0)), Some(&goal)));
+    }
+}
@@ -0,0 +1,89 @@
+use crate::answer::Answer;
+use crate::days::Part;
+use crate::error::Result;
+
+use std::io::BufRead;
+
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Metadata {
+    pub year: u32,
+    pub day: u32,
+    pub title: &'static str
+}
+
+
+/*
+ * Implemented by every day. The input is parsed once and then shared
+ * between both parts, so any part that needs to mutate it must clone.
+ */
+pub trait Solution {
+    type Input;
+
+    const DAY: u32;
+    const TITLE: &'static str;
+    const YEAR: u32 = 2023;
+
+    fn parse<B: BufRead>(&self, input: B) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+    fn part2(&self, input: &Self::Input) -> Result<Answer>;
+
+    /*
+     * Describes how a part treats the query, for days where a single
+     * item of the input can be looked at on its own. None when the day
+     * has nothing to explain.
+     */
+    fn explain(&self, _input: &Self::Input, _part: Part, _query: &str) -> Option<Result<String>> {
+        None
+    }
+}
+
+
+/*
+ * Object safe view of a Solution so the registry can hold every day
+ * together, regardless of their input types.
+ */
+pub trait DynSolution {
+    fn metadata(&self) -> Metadata;
+    fn parse<'a>(&'a self, input: &mut dyn BufRead) -> Result<Box<dyn ParsedInput + 'a>>;
+}
+
+pub trait ParsedInput {
+    fn solve(&self, part: Part) -> Result<Answer>;
+    fn explain(&self, part: Part, query: &str) -> Option<Result<String>>;
+}
+
+
+struct Parsed<'a, S: Solution> {
+    solution: &'a S,
+    input: S::Input
+}
+
+impl<S: Solution> ParsedInput for Parsed<'_, S> {
+    fn solve(&self, part: Part) -> Result<Answer> {
+        match part {
+            Part::P1 => self.solution.part1(&self.input),
+            Part::P2 => self.solution.part2(&self.input),
+        }
+    }
+
+    fn explain(&self, part: Part, query: &str) -> Option<Result<String>> {
+        self.solution.explain(&self.input, part, query)
+    }
+}
+
+impl<S: Solution> DynSolution for S {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: S::YEAR,
+            day: S::DAY,
+            title: S::TITLE
+        }
+    }
+
+    fn parse<'a>(&'a self, input: &mut dyn BufRead) -> Result<Box<dyn ParsedInput + 'a>> {
+        let input = Solution::parse(self, input)?;
+
+        Ok(Box::new(Parsed { solution: self, input }))
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+
+pub fn time<T, F>(f: F) -> (T, Duration)
+where F: FnOnce() -> T {
+    let start = Instant::now();
+    let output = f();
+
+    (output, start.elapsed())
+}
+
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Summary {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration
+}
+
+
+impl Summary {
+    /* The median of an even number of samples is the mean of the middle two */
+    pub fn from_samples(samples: &[Duration]) -> Option<Summary> {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let mid = sorted.len() / 2;
+
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        Some(Summary { min, median, max })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarises_samples() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(Summary::from_samples(&[]), None);
+        assert_eq!(
+            Summary::from_samples(&[ms(5), ms(1), ms(3)]),
+            Some(Summary { min: ms(1), median: ms(3), max: ms(5) })
+        );
+        assert_eq!(Summary::from_samples(&[ms(4), ms(1), ms(2), ms(9)]).unwrap().median, ms(3));
+    }
+}